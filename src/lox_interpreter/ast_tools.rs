@@ -2,13 +2,13 @@ use std::fmt;
 
 // NOTE: I really don't know where the calss generation is taknig me, will skip that for now and
 // see where it takes me.
-use super::{error::LoxError, token::Token};
+use super::{error::LoxError, token::Token, typechecker::Type};
 
 #[allow(unused_imports)]
 use crate::lox_interpreter::token::{Literal, TokenType};
 
 pub mod expr {
-    use super::Expr;
+    use super::{Argument, BindingPattern, Expr};
     use crate::lox_interpreter::{
         error::LoxError,
         token::{Literal, Token},
@@ -36,7 +36,56 @@ pub mod expr {
             &mut self,
             callee: &Expr,
             pren: &Token,
-            arguments: &Vec<Expr>,
+            arguments: &Vec<Argument>,
+        ) -> Result<T, LoxError>;
+        fn visit_compound_assign_expr(
+            &mut self,
+            target: &Expr,
+            operator: &Token,
+            value: &Expr,
+        ) -> Result<T, LoxError>;
+        fn visit_increment_expr(
+            &mut self,
+            target: &Expr,
+            operator: &Token,
+            prefix: bool,
+        ) -> Result<T, LoxError>;
+        fn visit_conditional_expr(
+            &mut self,
+            condition: &Expr,
+            then_branch: &Expr,
+            else_branch: &Expr,
+        ) -> Result<T, LoxError>;
+        fn visit_get_expr(
+            &mut self,
+            object: &Expr,
+            name: &Token,
+            optional: bool,
+        ) -> Result<T, LoxError>;
+        fn visit_list_expr(&mut self, elements: &Vec<Expr>) -> Result<T, LoxError>;
+        fn visit_index_expr(
+            &mut self,
+            object: &Expr,
+            bracket: &Token,
+            index: &Expr,
+        ) -> Result<T, LoxError>;
+        fn visit_set_index_expr(
+            &mut self,
+            object: &Expr,
+            bracket: &Token,
+            index: &Expr,
+            value: &Expr,
+        ) -> Result<T, LoxError>;
+        fn visit_map_expr(
+            &mut self,
+            brace: &Token,
+            entries: &Vec<(Token, Expr)>,
+        ) -> Result<T, LoxError>;
+        fn visit_destructure_assign_expr(
+            &mut self,
+            pattern: &BindingPattern,
+            equals: &Token,
+            value: &Expr,
         ) -> Result<T, LoxError>;
     }
 }
@@ -75,7 +124,55 @@ pub enum Expr {
     Call {
         callee: Box<Expr>,
         paren: Token,
-        arguments: Vec<Expr>,
+        arguments: Vec<Argument>,
+    },
+    // `target op= value`, the target is only evaluated once.
+    CompoundAssign {
+        target: Box<Expr>,
+        operator: Token,
+        value: Box<Expr>,
+    },
+    // Both `++` and `--`, prefix tells us whether to return the new or the old value.
+    Increment {
+        target: Box<Expr>,
+        operator: Token,
+        prefix: bool,
+    },
+    Conditional {
+        condition: Box<Expr>,
+        then_branch: Box<Expr>,
+        else_branch: Box<Expr>,
+    },
+    // Property access, optional is set for `?.` which gives back nil instead of failing on nil.
+    Get {
+        object: Box<Expr>,
+        name: Token,
+        optional: bool,
+    },
+    List {
+        elements: Vec<Expr>,
+    },
+    Index {
+        object: Box<Expr>,
+        bracket: Token,
+        index: Box<Expr>,
+    },
+    SetIndex {
+        object: Box<Expr>,
+        bracket: Token,
+        index: Box<Expr>,
+        value: Box<Expr>,
+    },
+    // `{name: value, "key": value}`, keys are always strings.
+    Map {
+        brace: Token,
+        entries: Vec<(Token, Expr)>,
+    },
+    // `[a, b] = value`, assigns every name in the pattern at once.
+    DestructureAssign {
+        pattern: BindingPattern,
+        equals: Token,
+        value: Box<Expr>,
     },
 }
 
@@ -109,12 +206,189 @@ impl Expr {
                 paren,
                 arguments,
             } => visitor.visit_call_expr(callee, paren, arguments),
+            Expr::CompoundAssign {
+                target,
+                operator,
+                value,
+            } => visitor.visit_compound_assign_expr(target, operator, value),
+            Expr::Increment {
+                target,
+                operator,
+                prefix,
+            } => visitor.visit_increment_expr(target, operator, *prefix),
+            Expr::Conditional {
+                condition,
+                then_branch,
+                else_branch,
+            } => visitor.visit_conditional_expr(condition, then_branch, else_branch),
+            Expr::Get {
+                object,
+                name,
+                optional,
+            } => visitor.visit_get_expr(object, name, *optional),
+            Expr::List { elements } => visitor.visit_list_expr(elements),
+            Expr::Index {
+                object,
+                bracket,
+                index,
+            } => visitor.visit_index_expr(object, bracket, index),
+            Expr::SetIndex {
+                object,
+                bracket,
+                index,
+                value,
+            } => visitor.visit_set_index_expr(object, bracket, index, value),
+            Expr::Map { brace, entries } => visitor.visit_map_expr(brace, entries),
+            Expr::DestructureAssign {
+                pattern,
+                equals,
+                value,
+            } => visitor.visit_destructure_assign_expr(pattern, equals, value),
         }
     }
 }
 
+// A single pattern of a match arm, `case 1, 2..5, n if n > 10 => ...` has three of them.
+#[derive(Debug, Clone)]
+pub enum Pattern {
+    Literal(Literal),
+    Range {
+        start: Literal,
+        end: Literal,
+        inclusive: bool,
+    },
+    // Matches anything and binds it to the name inside the arm.
+    Binding(Token),
+    // `_`, matches anything without binding it.
+    Wildcard,
+}
+
+impl fmt::Display for Pattern {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Pattern::Literal(value) => write!(f, "{}", value),
+            Pattern::Range {
+                start,
+                end,
+                inclusive,
+            } => write!(
+                f,
+                "{}{}{}",
+                start,
+                if *inclusive { "..=" } else { ".." },
+                end
+            ),
+            Pattern::Binding(name) => write!(f, "{}", name.lexeme),
+            Pattern::Wildcard => write!(f, "_"),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct MatchArm {
+    pub patterns: Vec<Pattern>,
+    pub guard: Option<Expr>,
+    pub body: Stmt,
+}
+
+// What a declaration, assignment or parameter binds to. Either a plain name or a pattern that
+// takes a list or map apart, `[a, b, ...rest]` and `{x, y: [first]}`.
+#[derive(Debug, Clone)]
+pub enum BindingPattern {
+    Name(Token),
+    List {
+        bracket: Token,
+        elements: Vec<BindingPattern>,
+        // Gets whatever elements are left over, as a list.
+        rest: Option<Token>,
+    },
+    // `{x}` is short for `{x: x}`.
+    Map {
+        brace: Token,
+        fields: Vec<(Token, BindingPattern)>,
+    },
+}
+
+impl BindingPattern {
+    // The name when the pattern is a plain one, as opposed to a destructuring one.
+    pub fn name(&self) -> Option<&Token> {
+        match self {
+            BindingPattern::Name(name) => Some(name),
+            _ => None,
+        }
+    }
+
+    // Every name the pattern binds, in the order they're written.
+    pub fn names(&self) -> Vec<&Token> {
+        match self {
+            BindingPattern::Name(name) => vec![name],
+            BindingPattern::List { elements, rest, .. } => elements
+                .iter()
+                .flat_map(|element| element.names())
+                .chain(rest.iter())
+                .collect(),
+            BindingPattern::Map { fields, .. } => {
+                fields.iter().flat_map(|(_, field)| field.names()).collect()
+            }
+        }
+    }
+}
+
+impl fmt::Display for BindingPattern {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            BindingPattern::Name(name) => write!(f, "{}", name.lexeme),
+            BindingPattern::List { elements, rest, .. } => {
+                let mut parts: Vec<String> = elements.iter().map(|e| e.to_string()).collect();
+                if let Some(rest) = rest {
+                    parts.push(format!("...{}", rest.lexeme));
+                }
+                write!(f, "[{}]", parts.join(", "))
+            }
+            BindingPattern::Map { fields, .. } => {
+                let parts: Vec<String> = fields
+                    .iter()
+                    .map(|(key, field)| match field {
+                        BindingPattern::Name(name) if name.lexeme == key.lexeme => {
+                            key.lexeme.clone()
+                        }
+                        _ => format!("{}: {}", key.lexeme, field),
+                    })
+                    .collect();
+                write!(f, "{{{}}}", parts.join(", "))
+            }
+        }
+    }
+}
+
+// `: Number` after a declaration. Only the type checker looks at these, the interpreter doesn't
+// care.
+#[derive(Debug, Clone)]
+pub struct TypeAnnotation {
+    pub name: Token,
+    pub ty: Type,
+}
+
+// `a`, `b = 2` or `...rest` in a function declaration.
+#[derive(Debug, Clone)]
+pub struct Parameter {
+    pub target: BindingPattern,
+    pub annotation: Option<TypeAnnotation>,
+    // Evaluated on every call that doesn't pass a value for the parameter.
+    pub default: Option<Expr>,
+    // Collects the positional arguments left over into a list, only allowed as the last parameter.
+    pub is_rest: bool,
+}
+
+// `value` or `name: value` in a call.
+#[derive(Debug, Clone)]
+pub struct Argument {
+    pub name: Option<Token>,
+    pub value: Expr,
+}
+
 pub mod stmt {
-    use super::{Expr, Stmt};
+    use super::{BindingPattern, Expr, MatchArm, Parameter, Stmt, TypeAnnotation};
     use crate::lox_interpreter::{error::LoxError, token::Token};
 
     pub trait Visitor<T> {
@@ -123,9 +397,16 @@ pub mod stmt {
         fn visit_print_stmt(&mut self, expression: &Expr) -> Result<T, LoxError>;
         fn visit_var_stmt(
             &mut self,
-            name: &Token,
+            target: &BindingPattern,
+            annotation: &Option<TypeAnnotation>,
             initializer: &Option<Expr>,
         ) -> Result<T, LoxError>;
+        fn visit_const_stmt(
+            &mut self,
+            name: &Token,
+            annotation: &Option<TypeAnnotation>,
+            initializer: &Expr,
+        ) -> Result<T, LoxError>;
         fn visit_if_statement(
             &mut self,
             condition: &Expr,
@@ -136,13 +417,44 @@ pub mod stmt {
             &mut self,
             condition: &Expr,
             body: &Box<Stmt>,
+            increment: &Option<Expr>,
+            label: &Option<Token>,
+        ) -> Result<T, LoxError>;
+        fn visit_for_in_stmt(
+            &mut self,
+            variable: &Token,
+            iterable: &Expr,
+            body: &Stmt,
+            label: &Option<Token>,
+        ) -> Result<T, LoxError>;
+        fn visit_do_while_stmt(
+            &mut self,
+            body: &Stmt,
+            condition: &Expr,
+            label: &Option<Token>,
+        ) -> Result<T, LoxError>;
+        fn visit_loop_stmt(&mut self, body: &Stmt, label: &Option<Token>) -> Result<T, LoxError>;
+        fn visit_match_stmt(
+            &mut self,
+            keyword: &Token,
+            subject: &Expr,
+            arms: &Vec<MatchArm>,
+        ) -> Result<T, LoxError>;
+        fn visit_break_stmt(
+            &mut self,
+            keyword: &Token,
+            label: &Option<Token>,
+        ) -> Result<T, LoxError>;
+        fn visit_continue_stmt(
+            &mut self,
+            keyword: &Token,
+            label: &Option<Token>,
         ) -> Result<T, LoxError>;
-        fn visit_break_stmt(&mut self) -> Result<T, LoxError>;
-        fn visit_continue_stmt(&mut self) -> Result<T, LoxError>;
         fn visit_function_stmt(
             &mut self,
             name: &Token,
-            paramaters: &Vec<Token>,
+            paramaters: &Vec<Parameter>,
+            return_type: &Option<TypeAnnotation>,
             body: &Vec<Stmt>,
         ) -> Result<T, LoxError>;
         fn visit_return_stmt(
@@ -155,14 +467,43 @@ pub mod stmt {
 
 #[derive(Debug, Clone)]
 pub enum Stmt {
-    Break,
-    Continue,
+    // The label, if any, names the loop to break out of or continue, otherwise it's the innermost
+    // one.
+    Break {
+        keyword: Token,
+        label: Option<Token>,
+    },
+    Continue {
+        keyword: Token,
+        label: Option<Token>,
+    },
     Block {
         statements: Vec<Stmt>,
     },
+    // NOTE: increment is only set for desugared for loops. It lives outside the body so that a
+    // continue doesn't skip it.
     While {
         condition: Expr,
         body: Box<Stmt>,
+        increment: Option<Expr>,
+        label: Option<Token>,
+    },
+    ForIn {
+        variable: Token,
+        iterable: Expr,
+        body: Box<Stmt>,
+        label: Option<Token>,
+    },
+    // `do body while (condition);`, the body always runs at least once.
+    DoWhile {
+        body: Box<Stmt>,
+        condition: Expr,
+        label: Option<Token>,
+    },
+    // `loop body`, only a break (or return) gets you out.
+    Loop {
+        body: Box<Stmt>,
+        label: Option<Token>,
     },
     Expression {
         expression: Expr,
@@ -171,17 +512,30 @@ pub enum Stmt {
         expression: Expr,
     },
     Var {
-        name: Token,
+        target: BindingPattern,
+        annotation: Option<TypeAnnotation>,
         initializer: Option<Expr>,
     },
+    // Same as Var, but the binding can't be assigned to afterwards.
+    Const {
+        name: Token,
+        annotation: Option<TypeAnnotation>,
+        initializer: Expr,
+    },
     If {
         condition: Expr,
         then_branch: Box<Stmt>,
         else_branch: Box<Option<Stmt>>,
     },
+    Match {
+        keyword: Token,
+        subject: Expr,
+        arms: Vec<MatchArm>,
+    },
     Function {
         name: Token,
-        paramaters: Vec<Token>,
+        paramaters: Vec<Parameter>,
+        return_type: Option<TypeAnnotation>,
         body: Vec<Stmt>,
     },
     Return {
@@ -200,21 +554,53 @@ impl Stmt {
             Stmt::Print {
                 expression: expresson,
             } => visitor.visit_print_stmt(expresson),
-            Stmt::Var { name, initializer } => visitor.visit_var_stmt(name, initializer),
+            Stmt::Var {
+                target,
+                annotation,
+                initializer,
+            } => visitor.visit_var_stmt(target, annotation, initializer),
+            Stmt::Const {
+                name,
+                annotation,
+                initializer,
+            } => visitor.visit_const_stmt(name, annotation, initializer),
             Stmt::NONE => unimplemented!(),
             Stmt::If {
                 condition,
                 then_branch,
                 else_branch,
             } => visitor.visit_if_statement(condition, then_branch, else_branch),
-            Stmt::While { condition, body } => visitor.visit_while_statement(condition, body),
-            Stmt::Break => visitor.visit_break_stmt(),
-            Stmt::Continue => visitor.visit_continue_stmt(),
+            Stmt::While {
+                condition,
+                body,
+                increment,
+                label,
+            } => visitor.visit_while_statement(condition, body, increment, label),
+            Stmt::ForIn {
+                variable,
+                iterable,
+                body,
+                label,
+            } => visitor.visit_for_in_stmt(variable, iterable, body, label),
+            Stmt::DoWhile {
+                body,
+                condition,
+                label,
+            } => visitor.visit_do_while_stmt(body, condition, label),
+            Stmt::Loop { body, label } => visitor.visit_loop_stmt(body, label),
+            Stmt::Match {
+                keyword,
+                subject,
+                arms,
+            } => visitor.visit_match_stmt(keyword, subject, arms),
+            Stmt::Break { keyword, label } => visitor.visit_break_stmt(keyword, label),
+            Stmt::Continue { keyword, label } => visitor.visit_continue_stmt(keyword, label),
             Stmt::Function {
                 name,
                 paramaters,
+                return_type,
                 body,
-            } => visitor.visit_function_stmt(name, paramaters, body),
+            } => visitor.visit_function_stmt(name, paramaters, return_type, body),
             Stmt::Return { keyword, value } => visitor.visit_return_stmt(keyword, value),
         }
     }
@@ -239,8 +625,7 @@ impl expr::Visitor<String> for ASTPrinter {
     }
 
     fn visit_literal_expr(&mut self, value: &Literal) -> Result<String, LoxError> {
-        // TODO: Check if the None case should be handled differently as they have done in the book.
-        Ok(value.to_string())
+        Ok(literal_source(value))
     }
 
     fn visit_grouping_expr(&mut self, expression: &Expr) -> Result<String, LoxError> {
@@ -252,28 +637,308 @@ impl expr::Visitor<String> for ASTPrinter {
     }
 
     fn visit_assignment_expr(&mut self, name: &Token, value: &Expr) -> Result<String, LoxError> {
-        self.parenthesize(name.lexeme.clone(), vec![value])
+        self.parenthesize(format!("= {}", name.lexeme), vec![value])
     }
 
-    // TODO: Do I really want to develop the printer class any further?
-    #[allow(unused)]
     fn visit_logical_expr(
         &mut self,
         left: &Expr,
         operator: &Token,
         right: &Expr,
     ) -> Result<String, LoxError> {
-        todo!()
+        self.parenthesize(operator.lexeme.clone(), vec![left, right])
     }
 
-    #[allow(unused)]
     fn visit_call_expr(
         &mut self,
         callee: &Expr,
-        pren: &Token,
-        arguments: &Vec<Expr>,
+        _pren: &Token,
+        arguments: &Vec<Argument>,
+    ) -> Result<String, LoxError> {
+        let mut string = format!("(call {}", callee.accept(self)?);
+        for argument in arguments {
+            string.push(' ');
+            if let Some(name) = &argument.name {
+                string.push_str(&format!("{}: ", name.lexeme));
+            }
+            string.push_str(&argument.value.accept(self)?);
+        }
+        string.push(')');
+        Ok(string)
+    }
+
+    fn visit_compound_assign_expr(
+        &mut self,
+        target: &Expr,
+        operator: &Token,
+        value: &Expr,
+    ) -> Result<String, LoxError> {
+        self.parenthesize(operator.lexeme.clone(), vec![target, value])
+    }
+
+    fn visit_increment_expr(
+        &mut self,
+        target: &Expr,
+        operator: &Token,
+        prefix: bool,
+    ) -> Result<String, LoxError> {
+        if prefix {
+            self.parenthesize(operator.lexeme.clone(), vec![target])
+        } else {
+            Ok(format!("({} {})", target.accept(self)?, operator.lexeme))
+        }
+    }
+
+    fn visit_conditional_expr(
+        &mut self,
+        condition: &Expr,
+        then_branch: &Expr,
+        else_branch: &Expr,
+    ) -> Result<String, LoxError> {
+        self.parenthesize("?:".to_string(), vec![condition, then_branch, else_branch])
+    }
+
+    fn visit_get_expr(
+        &mut self,
+        object: &Expr,
+        name: &Token,
+        optional: bool,
+    ) -> Result<String, LoxError> {
+        let operator = if optional { "?." } else { "." };
+        Ok(format!(
+            "({} {} {})",
+            operator,
+            object.accept(self)?,
+            name.lexeme
+        ))
+    }
+
+    fn visit_list_expr(&mut self, elements: &Vec<Expr>) -> Result<String, LoxError> {
+        self.parenthesize("list".to_string(), elements.iter().collect())
+    }
+
+    fn visit_index_expr(
+        &mut self,
+        object: &Expr,
+        _bracket: &Token,
+        index: &Expr,
+    ) -> Result<String, LoxError> {
+        self.parenthesize("[]".to_string(), vec![object, index])
+    }
+
+    fn visit_set_index_expr(
+        &mut self,
+        object: &Expr,
+        _bracket: &Token,
+        index: &Expr,
+        value: &Expr,
+    ) -> Result<String, LoxError> {
+        self.parenthesize("[]=".to_string(), vec![object, index, value])
+    }
+
+    fn visit_map_expr(
+        &mut self,
+        _brace: &Token,
+        entries: &Vec<(Token, Expr)>,
     ) -> Result<String, LoxError> {
-        todo!()
+        let mut string = "(map".to_string();
+        for (key, value) in entries {
+            string.push_str(&format!(" ({} {})", key.lexeme, value.accept(self)?));
+        }
+        string.push(')');
+        Ok(string)
+    }
+
+    fn visit_destructure_assign_expr(
+        &mut self,
+        pattern: &BindingPattern,
+        _equals: &Token,
+        value: &Expr,
+    ) -> Result<String, LoxError> {
+        self.parenthesize(format!("= {}", pattern), vec![value])
+    }
+}
+
+impl stmt::Visitor<String> for ASTPrinter {
+    fn visit_block_stmt(&mut self, statements: &Vec<Stmt>) -> Result<String, LoxError> {
+        self.parenthesize_statements("block".to_string(), statements)
+    }
+
+    fn visit_expression_stmt(&mut self, expression: &Expr) -> Result<String, LoxError> {
+        self.parenthesize(";".to_string(), vec![expression])
+    }
+
+    fn visit_print_stmt(&mut self, expression: &Expr) -> Result<String, LoxError> {
+        self.parenthesize("print".to_string(), vec![expression])
+    }
+
+    fn visit_var_stmt(
+        &mut self,
+        target: &BindingPattern,
+        annotation: &Option<TypeAnnotation>,
+        initializer: &Option<Expr>,
+    ) -> Result<String, LoxError> {
+        let name = format!("var {}{}", target, Self::annotation(annotation));
+        self.parenthesize(name, initializer.iter().collect())
+    }
+
+    fn visit_const_stmt(
+        &mut self,
+        name: &Token,
+        annotation: &Option<TypeAnnotation>,
+        initializer: &Expr,
+    ) -> Result<String, LoxError> {
+        let name = format!("const {}{}", name.lexeme, Self::annotation(annotation));
+        self.parenthesize(name, vec![initializer])
+    }
+
+    fn visit_if_statement(
+        &mut self,
+        condition: &Expr,
+        then_branch: &Stmt,
+        else_branch: &Option<Stmt>,
+    ) -> Result<String, LoxError> {
+        let mut string = format!(
+            "(if {} {}",
+            condition.accept(self)?,
+            then_branch.accept(self)?
+        );
+        if let Some(else_branch) = else_branch {
+            string.push_str(&format!(" {}", else_branch.accept(self)?));
+        }
+        string.push(')');
+        Ok(string)
+    }
+
+    fn visit_while_statement(
+        &mut self,
+        condition: &Expr,
+        body: &Box<Stmt>,
+        increment: &Option<Expr>,
+        label: &Option<Token>,
+    ) -> Result<String, LoxError> {
+        let mut string = format!(
+            "(while{} {} {}",
+            Self::label(label),
+            condition.accept(self)?,
+            body.accept(self)?
+        );
+        if let Some(increment) = increment {
+            string.push_str(&format!(" {}", increment.accept(self)?));
+        }
+        string.push(')');
+        Ok(string)
+    }
+
+    fn visit_for_in_stmt(
+        &mut self,
+        variable: &Token,
+        iterable: &Expr,
+        body: &Stmt,
+        label: &Option<Token>,
+    ) -> Result<String, LoxError> {
+        Ok(format!(
+            "(for-in{} {} {} {})",
+            Self::label(label),
+            variable.lexeme,
+            iterable.accept(self)?,
+            body.accept(self)?
+        ))
+    }
+
+    fn visit_do_while_stmt(
+        &mut self,
+        body: &Stmt,
+        condition: &Expr,
+        label: &Option<Token>,
+    ) -> Result<String, LoxError> {
+        Ok(format!(
+            "(do-while{} {} {})",
+            Self::label(label),
+            body.accept(self)?,
+            condition.accept(self)?
+        ))
+    }
+
+    fn visit_loop_stmt(&mut self, body: &Stmt, label: &Option<Token>) -> Result<String, LoxError> {
+        Ok(format!(
+            "(loop{} {})",
+            Self::label(label),
+            body.accept(self)?
+        ))
+    }
+
+    fn visit_match_stmt(
+        &mut self,
+        _keyword: &Token,
+        subject: &Expr,
+        arms: &Vec<MatchArm>,
+    ) -> Result<String, LoxError> {
+        let mut string = format!("(match {}", subject.accept(self)?);
+        for arm in arms {
+            let patterns: Vec<String> = arm.patterns.iter().map(|p| p.to_string()).collect();
+            string.push_str(&format!(" (case {}", patterns.join(", ")));
+            if let Some(guard) = &arm.guard {
+                string.push_str(&format!(" if {}", guard.accept(self)?));
+            }
+            string.push_str(&format!(" {})", arm.body.accept(self)?));
+        }
+        string.push(')');
+        Ok(string)
+    }
+
+    fn visit_break_stmt(
+        &mut self,
+        _keyword: &Token,
+        label: &Option<Token>,
+    ) -> Result<String, LoxError> {
+        Ok(format!("(break{})", Self::label(label)))
+    }
+
+    fn visit_continue_stmt(
+        &mut self,
+        _keyword: &Token,
+        label: &Option<Token>,
+    ) -> Result<String, LoxError> {
+        Ok(format!("(continue{})", Self::label(label)))
+    }
+
+    fn visit_function_stmt(
+        &mut self,
+        name: &Token,
+        paramaters: &Vec<Parameter>,
+        return_type: &Option<TypeAnnotation>,
+        body: &Vec<Stmt>,
+    ) -> Result<String, LoxError> {
+        let mut params = Vec::with_capacity(paramaters.len());
+        for param in paramaters {
+            let mut string = format!(
+                "{}{}{}",
+                if param.is_rest { "..." } else { "" },
+                param.target,
+                Self::annotation(&param.annotation)
+            );
+            if let Some(default) = &param.default {
+                string.push_str(&format!(" = {}", default.accept(self)?));
+            }
+            params.push(string);
+        }
+
+        let name = format!(
+            "fun {}({}){}",
+            name.lexeme,
+            params.join(", "),
+            Self::annotation(return_type)
+        );
+        self.parenthesize_statements(name, body)
+    }
+
+    fn visit_return_stmt(
+        &mut self,
+        _keyword: &Token,
+        value: &Option<Expr>,
+    ) -> Result<String, LoxError> {
+        self.parenthesize("return".to_string(), value.iter().collect())
     }
 }
 
@@ -286,6 +951,44 @@ impl ASTPrinter {
         expr.accept(self)
     }
 
+    // One line per top level statement.
+    pub fn print_statements(&mut self, statements: &[Stmt]) -> Result<String, LoxError> {
+        let mut printed = String::new();
+        for statement in statements {
+            printed.push_str(&statement.accept(self)?);
+            printed.push('\n');
+        }
+        Ok(printed)
+    }
+
+    fn parenthesize_statements(
+        &mut self,
+        name: String,
+        statements: &[Stmt],
+    ) -> Result<String, LoxError> {
+        let mut parenthesized_string = format!("({}", name);
+        for statement in statements {
+            parenthesized_string.push(' ');
+            parenthesized_string.push_str(&statement.accept(self)?);
+        }
+        parenthesized_string.push(')');
+        Ok(parenthesized_string)
+    }
+
+    fn annotation(annotation: &Option<TypeAnnotation>) -> String {
+        match annotation {
+            Some(annotation) => format!(": {}", annotation.ty),
+            None => String::new(),
+        }
+    }
+
+    fn label(label: &Option<Token>) -> String {
+        match label {
+            Some(label) => format!(" {}:", label.lexeme),
+            None => String::new(),
+        }
+    }
+
     // NOTE: format does return a string, but I couldn't figure out a good way to use that.
     pub fn parenthesize(
         &mut self,
@@ -305,6 +1008,481 @@ impl ASTPrinter {
     }
 }
 
+// Literals the way they'd be written in a script, so `"1"` and `1` don't look the same in a dump.
+fn literal_source(value: &Literal) -> String {
+    match value {
+        Literal::String(string) => format!("{:?}", string),
+        Literal::None => "nil".to_string(),
+        other => other.to_string(),
+    }
+}
+
+// Prints one node per line with its children indented under it, along with where the node's token
+// is in the source. Meant for looking at what the parser made of a script.
+#[derive(Default)]
+pub struct ASTTreePrinter;
+
+impl ASTTreePrinter {
+    pub fn new() -> Self {
+        ASTTreePrinter
+    }
+
+    pub fn print_statements(&mut self, statements: &[Stmt]) -> Result<String, LoxError> {
+        let mut printed = String::new();
+        for statement in statements {
+            printed.push_str(&statement.accept(self)?);
+        }
+        Ok(printed)
+    }
+
+    // A node and its already printed children, which get indented one level deeper.
+    fn node(label: String, token: Option<&Token>, children: Vec<String>) -> String {
+        let mut printed = label;
+        if let Some(token) = token {
+            printed.push_str(&format!(" [line {}, column {}]", token.line, token.column));
+        }
+        printed.push('\n');
+
+        for child in children {
+            for line in child.lines() {
+                printed.push_str("  ");
+                printed.push_str(line);
+                printed.push('\n');
+            }
+        }
+        printed
+    }
+
+    fn exprs(&mut self, expressions: &[&Expr]) -> Result<Vec<String>, LoxError> {
+        expressions
+            .iter()
+            .map(|expression| expression.accept(self))
+            .collect()
+    }
+
+    fn stmts(&mut self, statements: &[Stmt]) -> Result<Vec<String>, LoxError> {
+        statements
+            .iter()
+            .map(|statement| statement.accept(self))
+            .collect()
+    }
+
+    fn label(name: &str, label: &Option<Token>) -> String {
+        match label {
+            Some(label) => format!("{} {}:", name, label.lexeme),
+            None => name.to_string(),
+        }
+    }
+
+    fn annotated(name: String, annotation: &Option<TypeAnnotation>) -> String {
+        match annotation {
+            Some(annotation) => format!("{}: {}", name, annotation.ty),
+            None => name,
+        }
+    }
+}
+
+impl expr::Visitor<String> for ASTTreePrinter {
+    fn visit_binary_expr(
+        &mut self,
+        left: &Expr,
+        operator: &Token,
+        right: &Expr,
+    ) -> Result<String, LoxError> {
+        let children = self.exprs(&[left, right])?;
+        Ok(Self::node(
+            format!("Binary {}", operator.lexeme),
+            Some(operator),
+            children,
+        ))
+    }
+
+    fn visit_grouping_expr(&mut self, expression: &Expr) -> Result<String, LoxError> {
+        let children = self.exprs(&[expression])?;
+        Ok(Self::node("Grouping".to_string(), None, children))
+    }
+
+    fn visit_literal_expr(&mut self, value: &Literal) -> Result<String, LoxError> {
+        Ok(Self::node(
+            format!("Literal {}", literal_source(value)),
+            None,
+            Vec::new(),
+        ))
+    }
+
+    fn visit_unary_expr(&mut self, operator: &Token, right: &Expr) -> Result<String, LoxError> {
+        let children = self.exprs(&[right])?;
+        Ok(Self::node(
+            format!("Unary {}", operator.lexeme),
+            Some(operator),
+            children,
+        ))
+    }
+
+    fn visit_variable_expr(&mut self, name: &Token) -> Result<String, LoxError> {
+        Ok(Self::node(
+            format!("Variable {}", name.lexeme),
+            Some(name),
+            Vec::new(),
+        ))
+    }
+
+    fn visit_assignment_expr(&mut self, name: &Token, value: &Expr) -> Result<String, LoxError> {
+        let children = self.exprs(&[value])?;
+        Ok(Self::node(
+            format!("Assign {}", name.lexeme),
+            Some(name),
+            children,
+        ))
+    }
+
+    fn visit_logical_expr(
+        &mut self,
+        left: &Expr,
+        operator: &Token,
+        right: &Expr,
+    ) -> Result<String, LoxError> {
+        let children = self.exprs(&[left, right])?;
+        Ok(Self::node(
+            format!("Logical {}", operator.lexeme),
+            Some(operator),
+            children,
+        ))
+    }
+
+    fn visit_call_expr(
+        &mut self,
+        callee: &Expr,
+        pren: &Token,
+        arguments: &Vec<Argument>,
+    ) -> Result<String, LoxError> {
+        let mut children = self.exprs(&[callee])?;
+        for argument in arguments {
+            let value = argument.value.accept(self)?;
+            children.push(match &argument.name {
+                Some(name) => {
+                    Self::node(format!("Keyword {}", name.lexeme), Some(name), vec![value])
+                }
+                None => value,
+            });
+        }
+        Ok(Self::node("Call".to_string(), Some(pren), children))
+    }
+
+    fn visit_compound_assign_expr(
+        &mut self,
+        target: &Expr,
+        operator: &Token,
+        value: &Expr,
+    ) -> Result<String, LoxError> {
+        let children = self.exprs(&[target, value])?;
+        Ok(Self::node(
+            format!("CompoundAssign {}", operator.lexeme),
+            Some(operator),
+            children,
+        ))
+    }
+
+    fn visit_increment_expr(
+        &mut self,
+        target: &Expr,
+        operator: &Token,
+        prefix: bool,
+    ) -> Result<String, LoxError> {
+        let children = self.exprs(&[target])?;
+        let fixity = if prefix { "prefix" } else { "postfix" };
+        Ok(Self::node(
+            format!("Increment {} {}", operator.lexeme, fixity),
+            Some(operator),
+            children,
+        ))
+    }
+
+    fn visit_conditional_expr(
+        &mut self,
+        condition: &Expr,
+        then_branch: &Expr,
+        else_branch: &Expr,
+    ) -> Result<String, LoxError> {
+        let children = self.exprs(&[condition, then_branch, else_branch])?;
+        Ok(Self::node("Conditional".to_string(), None, children))
+    }
+
+    fn visit_get_expr(
+        &mut self,
+        object: &Expr,
+        name: &Token,
+        optional: bool,
+    ) -> Result<String, LoxError> {
+        let children = self.exprs(&[object])?;
+        let kind = if optional { "OptionalGet" } else { "Get" };
+        Ok(Self::node(
+            format!("{} {}", kind, name.lexeme),
+            Some(name),
+            children,
+        ))
+    }
+
+    fn visit_list_expr(&mut self, elements: &Vec<Expr>) -> Result<String, LoxError> {
+        let children = self.exprs(&elements.iter().collect::<Vec<&Expr>>())?;
+        Ok(Self::node("List".to_string(), None, children))
+    }
+
+    fn visit_index_expr(
+        &mut self,
+        object: &Expr,
+        bracket: &Token,
+        index: &Expr,
+    ) -> Result<String, LoxError> {
+        let children = self.exprs(&[object, index])?;
+        Ok(Self::node("Index".to_string(), Some(bracket), children))
+    }
+
+    fn visit_set_index_expr(
+        &mut self,
+        object: &Expr,
+        bracket: &Token,
+        index: &Expr,
+        value: &Expr,
+    ) -> Result<String, LoxError> {
+        let children = self.exprs(&[object, index, value])?;
+        Ok(Self::node("SetIndex".to_string(), Some(bracket), children))
+    }
+
+    fn visit_map_expr(
+        &mut self,
+        brace: &Token,
+        entries: &Vec<(Token, Expr)>,
+    ) -> Result<String, LoxError> {
+        let mut children = Vec::with_capacity(entries.len());
+        for (key, value) in entries {
+            let value = value.accept(self)?;
+            children.push(Self::node(
+                format!("Entry {}", key.lexeme),
+                Some(key),
+                vec![value],
+            ));
+        }
+        Ok(Self::node("Map".to_string(), Some(brace), children))
+    }
+
+    fn visit_destructure_assign_expr(
+        &mut self,
+        pattern: &BindingPattern,
+        equals: &Token,
+        value: &Expr,
+    ) -> Result<String, LoxError> {
+        let children = self.exprs(&[value])?;
+        Ok(Self::node(
+            format!("DestructureAssign {}", pattern),
+            Some(equals),
+            children,
+        ))
+    }
+}
+
+impl stmt::Visitor<String> for ASTTreePrinter {
+    fn visit_block_stmt(&mut self, statements: &Vec<Stmt>) -> Result<String, LoxError> {
+        let children = self.stmts(statements)?;
+        Ok(Self::node("Block".to_string(), None, children))
+    }
+
+    fn visit_expression_stmt(&mut self, expression: &Expr) -> Result<String, LoxError> {
+        let children = self.exprs(&[expression])?;
+        Ok(Self::node("Expression".to_string(), None, children))
+    }
+
+    fn visit_print_stmt(&mut self, expression: &Expr) -> Result<String, LoxError> {
+        let children = self.exprs(&[expression])?;
+        Ok(Self::node("Print".to_string(), None, children))
+    }
+
+    fn visit_var_stmt(
+        &mut self,
+        target: &BindingPattern,
+        annotation: &Option<TypeAnnotation>,
+        initializer: &Option<Expr>,
+    ) -> Result<String, LoxError> {
+        let children = self.exprs(&initializer.iter().collect::<Vec<&Expr>>())?;
+        Ok(Self::node(
+            Self::annotated(format!("Var {}", target), annotation),
+            target.name(),
+            children,
+        ))
+    }
+
+    fn visit_const_stmt(
+        &mut self,
+        name: &Token,
+        annotation: &Option<TypeAnnotation>,
+        initializer: &Expr,
+    ) -> Result<String, LoxError> {
+        let children = self.exprs(&[initializer])?;
+        Ok(Self::node(
+            Self::annotated(format!("Const {}", name.lexeme), annotation),
+            Some(name),
+            children,
+        ))
+    }
+
+    fn visit_if_statement(
+        &mut self,
+        condition: &Expr,
+        then_branch: &Stmt,
+        else_branch: &Option<Stmt>,
+    ) -> Result<String, LoxError> {
+        let mut children = self.exprs(&[condition])?;
+        children.push(then_branch.accept(self)?);
+        if let Some(else_branch) = else_branch {
+            let else_branch = else_branch.accept(self)?;
+            children.push(Self::node("Else".to_string(), None, vec![else_branch]));
+        }
+        Ok(Self::node("If".to_string(), None, children))
+    }
+
+    fn visit_while_statement(
+        &mut self,
+        condition: &Expr,
+        body: &Box<Stmt>,
+        increment: &Option<Expr>,
+        label: &Option<Token>,
+    ) -> Result<String, LoxError> {
+        let mut children = self.exprs(&[condition])?;
+        children.push(body.accept(self)?);
+        if let Some(increment) = increment {
+            let increment = increment.accept(self)?;
+            children.push(Self::node("Increment".to_string(), None, vec![increment]));
+        }
+        Ok(Self::node(
+            Self::label("While", label),
+            label.as_ref(),
+            children,
+        ))
+    }
+
+    fn visit_for_in_stmt(
+        &mut self,
+        variable: &Token,
+        iterable: &Expr,
+        body: &Stmt,
+        label: &Option<Token>,
+    ) -> Result<String, LoxError> {
+        let mut children = self.exprs(&[iterable])?;
+        children.push(body.accept(self)?);
+        Ok(Self::node(
+            format!("{} {}", Self::label("ForIn", label), variable.lexeme),
+            Some(variable),
+            children,
+        ))
+    }
+
+    fn visit_do_while_stmt(
+        &mut self,
+        body: &Stmt,
+        condition: &Expr,
+        label: &Option<Token>,
+    ) -> Result<String, LoxError> {
+        let children = vec![body.accept(self)?, condition.accept(self)?];
+        Ok(Self::node(
+            Self::label("DoWhile", label),
+            label.as_ref(),
+            children,
+        ))
+    }
+
+    fn visit_loop_stmt(&mut self, body: &Stmt, label: &Option<Token>) -> Result<String, LoxError> {
+        let children = vec![body.accept(self)?];
+        Ok(Self::node(
+            Self::label("Loop", label),
+            label.as_ref(),
+            children,
+        ))
+    }
+
+    fn visit_match_stmt(
+        &mut self,
+        keyword: &Token,
+        subject: &Expr,
+        arms: &Vec<MatchArm>,
+    ) -> Result<String, LoxError> {
+        let mut children = self.exprs(&[subject])?;
+        for arm in arms {
+            let patterns: Vec<String> = arm.patterns.iter().map(|p| p.to_string()).collect();
+            let mut arm_children = Vec::new();
+            if let Some(guard) = &arm.guard {
+                let guard = guard.accept(self)?;
+                arm_children.push(Self::node("Guard".to_string(), None, vec![guard]));
+            }
+            arm_children.push(arm.body.accept(self)?);
+            children.push(Self::node(
+                format!("Case {}", patterns.join(", ")),
+                None,
+                arm_children,
+            ));
+        }
+        Ok(Self::node("Match".to_string(), Some(keyword), children))
+    }
+
+    fn visit_break_stmt(
+        &mut self,
+        keyword: &Token,
+        label: &Option<Token>,
+    ) -> Result<String, LoxError> {
+        Ok(Self::node(
+            Self::label("Break", label),
+            Some(keyword),
+            Vec::new(),
+        ))
+    }
+
+    fn visit_continue_stmt(
+        &mut self,
+        keyword: &Token,
+        label: &Option<Token>,
+    ) -> Result<String, LoxError> {
+        Ok(Self::node(
+            Self::label("Continue", label),
+            Some(keyword),
+            Vec::new(),
+        ))
+    }
+
+    fn visit_function_stmt(
+        &mut self,
+        name: &Token,
+        paramaters: &Vec<Parameter>,
+        return_type: &Option<TypeAnnotation>,
+        body: &Vec<Stmt>,
+    ) -> Result<String, LoxError> {
+        let mut children = Vec::new();
+        for param in paramaters {
+            let rest = if param.is_rest { "..." } else { "" };
+            let label = Self::annotated(
+                format!("Parameter {}{}", rest, param.target),
+                &param.annotation,
+            );
+            let default = self.exprs(&param.default.iter().collect::<Vec<&Expr>>())?;
+            children.push(Self::node(label, param.target.name(), default));
+        }
+        children.extend(self.stmts(body)?);
+
+        Ok(Self::node(
+            Self::annotated(format!("Function {}", name.lexeme), return_type),
+            Some(name),
+            children,
+        ))
+    }
+
+    fn visit_return_stmt(
+        &mut self,
+        keyword: &Token,
+        value: &Option<Expr>,
+    ) -> Result<String, LoxError> {
+        let children = self.exprs(&value.iter().collect::<Vec<&Expr>>())?;
+        Ok(Self::node("Return".to_string(), Some(keyword), children))
+    }
+}
+
 // NOTE: Finally got them to work, don't ask me how much dry running I did on this one.
 #[test]
 fn test_print_tree() {
@@ -342,3 +1520,53 @@ fn test_print_tree() {
         "(* (- 123) (group 45.67))"
     );
 }
+
+#[test]
+fn test_print_operator_precedence() {
+    use crate::lox_interpreter::{parser::Parser, scanner::Scanner};
+
+    let tokens = Scanner::new("-2 ** 3 ** 2 % 4 ~/ 2 + 1 << 2 & 7 ^ 1 | 8 < 9;".into())
+        .scan_tokens()
+        .unwrap();
+    let statements = Parser::new(tokens).parse().unwrap();
+    let Stmt::Expression { expression } = &statements[0] else {
+        panic!("Expected an expression statement.");
+    };
+
+    let mut printer = ASTPrinter;
+    assert_eq!(
+        printer.print(expression.clone()).unwrap(),
+        "(< (| (^ (& (<< (+ (~/ (% (- (** 2 (** 3 2))) 4) 2) 1) 2) 7) 1) 8) 9)"
+    );
+}
+
+#[test]
+fn test_print_statements() {
+    use crate::lox_interpreter::{parser::Parser, scanner::Scanner};
+
+    let source = "fun f(a, b: Number = 2, ...rest) { return a ?? \"none\"; }\n\
+                  outer: for (x in [1, 2]) { if (x > 1 and f(x, b: 3)) break outer; else x = nil; }";
+    let tokens = Scanner::new(source.into()).scan_tokens().unwrap();
+    let statements = Parser::new(tokens).parse().unwrap();
+
+    assert_eq!(
+        ASTPrinter::new().print_statements(&statements).unwrap(),
+        "(fun f(a, b: Number = 2, ...rest) (return (?? a \"none\")))\n\
+         (for-in outer: x (list 1 2) (block (if (and (> x 1) (call f x b: 3)) (break outer:) (; (= x nil)))))\n"
+    );
+}
+
+#[test]
+fn test_print_tree_with_positions() {
+    use crate::lox_interpreter::{parser::Parser, scanner::Scanner};
+
+    let tokens = Scanner::new("var x = 1;\nprint -x;".into())
+        .scan_tokens()
+        .unwrap();
+    let statements = Parser::new(tokens).parse().unwrap();
+
+    assert_eq!(
+        ASTTreePrinter::new().print_statements(&statements).unwrap(),
+        "Var x [line 1, column 5]\n  Literal 1\nPrint\n  Unary - [line 2, column 7]\n    Variable x [line 2, column 8]\n"
+    );
+}
@@ -1,4 +1,8 @@
-use std::{cell::RefCell, collections::HashMap, rc::Rc};
+use std::{
+    cell::RefCell,
+    collections::{HashMap, HashSet},
+    rc::Rc,
+};
 
 use super::{error::LoxError, interpreter::Object, token::Token};
 
@@ -9,7 +13,8 @@ pub struct Environment {
     // share the same parent kind of thing.
     enclosing: Option<Rc<RefCell<Environment>>>,
     values: HashMap<String, Object>,
-    pub is_enclosed_in_loop: bool,
+    // Names in values that were declared with const.
+    constants: HashSet<String>,
 }
 
 impl Environment {
@@ -17,12 +22,33 @@ impl Environment {
         Environment {
             enclosing: None,
             values: HashMap::new(),
-            is_enclosed_in_loop: false,
+            constants: HashSet::new(),
         }
     }
 
-    pub fn define(&mut self, name: String, value: Object) -> Result<(), LoxError> {
-        self.values.insert(name, value);
+    pub fn define(&mut self, name: &Token, value: Object) -> Result<(), LoxError> {
+        // NOTE: Redeclaring a plain variable is fine, a constant has to stay put.
+        if self.constants.contains(&name.lexeme) {
+            return Err(LoxError::Runtime {
+                token: name.clone(),
+                message: format!("Cannot redeclare constant '{}'.", name.lexeme),
+            });
+        }
+        self.values.insert(name.lexeme.clone(), value);
+        Ok(())
+    }
+
+    pub fn define_constant(&mut self, name: &Token, value: Object) -> Result<(), LoxError> {
+        self.define(name, value)?;
+        self.constants.insert(name.lexeme.clone());
+        Ok(())
+    }
+
+    // For the natives and values the interpreter puts in place before any script runs, there's
+    // no token to point at and nothing to clash with yet. They aren't constants, a script is free
+    // to declare its own `E` or `fun max() {}`.
+    pub fn define_builtin(&mut self, name: &str, value: Object) -> Result<(), LoxError> {
+        self.values.insert(name.to_string(), value);
         Ok(())
     }
 
@@ -30,7 +56,7 @@ impl Environment {
         Environment {
             enclosing: Some(Rc::clone(enclosing)),
             values: HashMap::new(),
-            is_enclosed_in_loop: enclosing.borrow().is_enclosed_in_loop,
+            constants: HashSet::new(),
         }
     }
 
@@ -53,6 +79,12 @@ impl Environment {
     pub fn assign(&mut self, name: &Token, value: Object) -> Result<(), LoxError> {
         let key = &name.lexeme;
         if self.values.contains_key(key) {
+            if self.constants.contains(key) {
+                return Err(LoxError::Runtime {
+                    token: name.clone(),
+                    message: format!("Cannot assign to constant '{}'.", key),
+                });
+            }
             self.values.insert(key.clone(), value);
             Ok(())
         } else {
@@ -26,6 +26,28 @@ pub fn report_parse_error(token: &Token, message: &str) {
     }
 }
 
+// Warnings are only reported, they never stop the program from running.
+pub fn report_warning(token: &Token, message: &str) {
+    eprintln!(
+        "[column: {}, line {}] Warning at '{}': {}",
+        token.column, token.line, token.lexeme, message
+    );
+}
+
+pub fn report_type_error(token: &Token, message: &str) {
+    eprintln!(
+        "[column: {}, line {}] Type Error at '{}': {}",
+        token.column, token.line, token.lexeme, message
+    );
+}
+
+pub fn report_runtime_error(token: &Token, message: &str) {
+    eprintln!(
+        "[column: {}, line {}] Runtime Error at '{}': {}",
+        token.column, token.line, token.lexeme, message
+    );
+}
+
 #[derive(Error, Debug)]
 pub enum LoxError {
     #[error("Error Converting to sting from UTF8")]
@@ -36,12 +58,27 @@ pub enum LoxError {
     IoError(#[from] io::Error),
     #[error("Error Parsing File")]
     Parse,
+    #[error("Error Resolving File")]
+    Resolve,
+    #[error("Error Type Checking File")]
+    TypeCheck,
     #[error("Runtime enrorn: Message: {message:?}")]
     Runtime { token: Token, message: String },
+    // A native that reached outside the interpreter failed, the io::Error is kept as the source.
+    #[error("Runtime Io Error: Message: {message:?}")]
+    RuntimeIo {
+        token: Token,
+        message: String,
+        #[source]
+        source: io::Error,
+    },
     #[error("Break Statement")]
-    BreakStmtError,
+    BreakStmtError { label: Option<String> },
     #[error("Continue Statement")]
-    ContinueStmtError,
+    ContinueStmtError { label: Option<String> },
     #[error("Return Error {value:?}")]
     Return { value: Object },
+    // The script ran past the interpreter's deadline.
+    #[error("Timed Out")]
+    Timeout,
 }
@@ -2,41 +2,51 @@ use core::fmt;
 use std::{cell::RefCell, rc::Rc};
 
 use super::{
-    ast_tools::Stmt,
+    ast_tools::{Parameter, Stmt},
     environment::Environment,
     error::LoxError,
-    interpreter::{Interpreter, Object},
+    interpreter::{destructure, Interpreter, Object},
     token::Token,
 };
 
 #[derive(Debug, Clone)]
 pub struct Function {
     pub name: Token,
-    pub params: Vec<Token>,
+    pub params: Vec<Parameter>,
     pub body: Vec<Stmt>,
     pub closure: Rc<RefCell<Environment>>,
 }
 
 // TODO: Implement the call and arity methods for the Function struct.
 impl Function {
-    pub fn arity(&self) -> usize {
-        self.params.len()
+    // Parameters the caller has to pass a value for.
+    pub fn min_arity(&self) -> usize {
+        self.params
+            .iter()
+            .filter(|param| param.default.is_none() && !param.is_rest)
+            .count()
+    }
+
+    // None when a rest parameter soaks up any number of extra arguments.
+    pub fn max_arity(&self) -> Option<usize> {
+        if self.params.iter().any(|param| param.is_rest) {
+            None
+        } else {
+            Some(self.params.len())
+        }
     }
 
     pub fn call(
         &self,
         interpreter: &mut Interpreter,
-        args: &Vec<Object>,
+        paren: &Token,
+        args: Vec<Object>,
+        named: Vec<(Token, Object)>,
     ) -> Result<Object, LoxError> {
         let env = Rc::new(RefCell::new(Environment::create_enclosing_for_env(
             &self.closure,
         )));
-
-        for (param, arg) in self.params.iter().zip(args.iter()) {
-            // NOTE: Borrow checks won't let me do something like let env = env.borrow_mut and then
-            // move out so this is what I do.
-            env.borrow_mut().define(param.lexeme.clone(), arg.clone())?;
-        }
+        self.bind_arguments(interpreter, paren, &env, args, named)?;
 
         // println!("Function body: {:?}", self.body);
         match interpreter.execute_block(&self.body, env) {
@@ -48,6 +58,110 @@ impl Function {
             Ok(..) => Ok(Object::NONE),
         }
     }
+
+    fn bind_arguments(
+        &self,
+        interpreter: &mut Interpreter,
+        paren: &Token,
+        env: &Rc<RefCell<Environment>>,
+        args: Vec<Object>,
+        named: Vec<(Token, Object)>,
+    ) -> Result<(), LoxError> {
+        let (rest, params) = match self.params.split_last() {
+            Some((last, others)) if last.is_rest => (Some(last), others),
+            _ => (None, &self.params[..]),
+        };
+        let given = args.len() + named.len();
+
+        // Positional arguments fill the parameters in order, whatever is left goes to rest.
+        let mut args = args.into_iter();
+        let mut values: Vec<Option<Object>> = params.iter().map(|_| args.next()).collect();
+        let extra: Vec<Object> = args.collect();
+        if !extra.is_empty() && rest.is_none() {
+            return Err(arity_error(
+                paren,
+                self.min_arity(),
+                self.max_arity(),
+                given,
+            ));
+        }
+
+        for (name, value) in named {
+            let Some(index) = params.iter().position(|param| {
+                param
+                    .target
+                    .name()
+                    .is_some_and(|param| param.lexeme == name.lexeme)
+            }) else {
+                return Err(LoxError::Runtime {
+                    message: format!(
+                        "'{}' has no parameter named '{}'.",
+                        self.name.lexeme, name.lexeme
+                    ),
+                    token: name,
+                });
+            };
+            if values[index].is_some() {
+                return Err(LoxError::Runtime {
+                    message: format!("Got more than one value for parameter '{}'.", name.lexeme),
+                    token: name,
+                });
+            }
+            values[index] = Some(value);
+        }
+
+        for (param, value) in params.iter().zip(values) {
+            let value = match (value, &param.default) {
+                (Some(value), _) => value,
+                // NOTE: Defaults run inside the call's environment so they can use the closure as
+                // well as the parameters before them, `fun f(a, b = a * 2)`.
+                (None, Some(default)) => interpreter.evaluate_in(default, Rc::clone(env))?,
+                (None, None) if given < self.min_arity() => {
+                    return Err(arity_error(
+                        paren,
+                        self.min_arity(),
+                        self.max_arity(),
+                        given,
+                    ))
+                }
+                (None, None) => {
+                    return Err(LoxError::Runtime {
+                        token: paren.clone(),
+                        message: format!("Missing argument for parameter '{}'.", param.target),
+                    })
+                }
+            };
+            // NOTE: Borrow checks won't let me do something like let env = env.borrow_mut and then
+            // move out so this is what I do.
+            destructure(&param.target, value, &mut |name, value| {
+                env.borrow_mut().define(name, value)
+            })?;
+        }
+
+        if let Some(rest) = rest {
+            destructure(
+                &rest.target,
+                Object::List(Rc::new(RefCell::new(extra))),
+                &mut |name, value| env.borrow_mut().define(name, value),
+            )?;
+        }
+
+        Ok(())
+    }
+}
+
+// Shared by lox and native functions. A max of None means there's no upper limit.
+pub fn arity_error(paren: &Token, min: usize, max: Option<usize>, given: usize) -> LoxError {
+    let expected = match max {
+        Some(max) if max == min => min.to_string(),
+        Some(max) => format!("{} to {}", min, max),
+        None => format!("at least {}", min),
+    };
+
+    LoxError::Runtime {
+        token: paren.clone(),
+        message: format!("Expected {} arguments, but got {}.", expected, given),
+    }
 }
 
 impl fmt::Display for Function {
@@ -55,3 +169,62 @@ impl fmt::Display for Function {
         write!(f, "function: {}", self.name)
     }
 }
+
+/// Signature shared by all the built-in functions. They get the paren of the call so errors can
+/// point at it.
+pub type NativeFn = fn(&mut Interpreter, &Token, &[Object]) -> Result<Object, LoxError>;
+
+#[derive(Debug, Clone)]
+pub struct NativeFunction {
+    pub name: &'static str,
+    pub min_arity: usize,
+    pub max_arity: usize,
+    pub function: NativeFn,
+}
+
+impl NativeFunction {
+    pub fn new(name: &'static str, min_arity: usize, max_arity: usize, function: NativeFn) -> Self {
+        NativeFunction {
+            name,
+            min_arity,
+            max_arity,
+            function,
+        }
+    }
+
+    pub fn call(
+        &self,
+        interpreter: &mut Interpreter,
+        paren: &Token,
+        args: &[Object],
+    ) -> Result<Object, LoxError> {
+        (self.function)(interpreter, paren, args)
+    }
+}
+
+impl fmt::Display for NativeFunction {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "<native fn {}>", self.name)
+    }
+}
+
+#[test]
+fn test_arity_error_messages() {
+    use crate::lox_interpreter::token::{Literal, TokenType};
+
+    let paren = Token::new(TokenType::RIGHT_PAREN, ")".to_string(), Literal::None, 1, 1);
+    let message = |min, max, given| match arity_error(&paren, min, max, given) {
+        LoxError::Runtime { message, .. } => message,
+        other => panic!("Expected a runtime error, got {:?}", other),
+    };
+
+    assert_eq!(message(2, Some(2), 1), "Expected 2 arguments, but got 1.");
+    assert_eq!(
+        message(1, Some(3), 4),
+        "Expected 1 to 3 arguments, but got 4."
+    );
+    assert_eq!(
+        message(1, None, 0),
+        "Expected at least 1 arguments, but got 0."
+    );
+}
@@ -1,10 +1,14 @@
 use crate::lox_interpreter::error::LoxError;
-use std::{cell::RefCell, fmt, rc::Rc};
+use std::{cell::RefCell, cmp::Ordering, collections::BTreeMap, fmt, rc::Rc, time::Instant};
 
 use super::{
-    ast_tools::{expr, stmt, Expr, Stmt},
+    ast_tools::{
+        expr, stmt, Argument, BindingPattern, Expr, MatchArm, Parameter, Pattern, Stmt,
+        TypeAnnotation,
+    },
     environment::Environment,
-    function::Function,
+    function::{arity_error, Function, NativeFunction},
+    stdlib::{self, iter::Range, random::Rng, regex::RegexCache, VARIADIC},
     token::{Literal, Token, TokenType},
 };
 
@@ -14,58 +18,209 @@ pub enum Object {
     Boolean(bool),
     NONE,
     Number(f64),
+    Integer(i64),
     String(String),
     Callable(Function),
+    NativeFunction(NativeFunction),
+    // NOTE: Lists are shared, assigning one to another variable doesn't copy it, same as Java.
+    List(Rc<RefCell<Vec<Object>>>),
+    // NOTE: Shared like lists. Keys are kept sorted so printing a map always gives the same output.
+    Map(Rc<RefCell<BTreeMap<String, Object>>>),
+    Range(Range),
 }
 
 impl Object {
     fn equals(&self, value: &Object) -> bool {
+        self.equals_in(value, &mut Vec::new())
+    }
+
+    // The pairs of lists and maps being compared further up are kept in `parents`. Meeting one of
+    // them again means the structures contain themselves, the pair is taken as equal there and
+    // the rest of the elements decide.
+    fn equals_in(&self, value: &Object, parents: &mut Vec<(*const (), *const ())>) -> bool {
         match (self, value) {
             (Object::Boolean(l), Object::Boolean(r)) => l == r,
             (Object::NONE, Object::NONE) => true,
             (Object::NONE, _) => false,
             (_, Object::NONE) => false,
-            (Object::Number(l), Object::Number(r)) => l == r,
+            (Object::Integer(l), Object::Integer(r)) => l == r,
             (Object::String(l), Object::String(r)) => l.eq(r),
-            _ => false,
+            (Object::List(l), Object::List(r)) => {
+                let pair = (Rc::as_ptr(l) as *const (), Rc::as_ptr(r) as *const ());
+                if Rc::ptr_eq(l, r) || parents.contains(&pair) {
+                    return true;
+                }
+                let (l, r) = (l.borrow(), r.borrow());
+                parents.push(pair);
+                let equal = l.len() == r.len()
+                    && l.iter().zip(r.iter()).all(|(l, r)| l.equals_in(r, parents));
+                parents.pop();
+                equal
+            }
+            (Object::Map(l), Object::Map(r)) => {
+                let pair = (Rc::as_ptr(l) as *const (), Rc::as_ptr(r) as *const ());
+                if Rc::ptr_eq(l, r) || parents.contains(&pair) {
+                    return true;
+                }
+                let (l, r) = (l.borrow(), r.borrow());
+                parents.push(pair);
+                let equal = l.len() == r.len()
+                    && l.iter()
+                        .zip(r.iter())
+                        .all(|((lk, lv), (rk, rv))| lk == rk && lv.equals_in(rv, parents));
+                parents.pop();
+                equal
+            }
+            (Object::Range(l), Object::Range(r)) => l == r,
+            // Integers and floats compare by value, so 1 == 1.0.
+            (l, r) => match (l.as_float(), r.as_float()) {
+                (Some(l), Some(r)) => l == r,
+                _ => false,
+            },
+        }
+    }
+
+    /// Widens integers to floats, used whenever an operation mixes the two number types.
+    pub fn as_float(&self) -> Option<f64> {
+        match self {
+            Object::Number(n) => Some(*n),
+            Object::Integer(i) => Some(*i as f64),
+            _ => None,
         }
     }
 }
 
-impl fmt::Display for Object {
-    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+impl Object {
+    // `parents` holds the lists and maps being printed further up, one that contains itself
+    // shows up as `[...]` or `{...}` instead of being printed forever.
+    fn write(&self, f: &mut fmt::Formatter<'_>, parents: &mut Vec<*const ()>) -> fmt::Result {
         match self {
             Object::Boolean(b) => write!(f, "{}", b),
             Object::Number(n) => write!(f, "{}", n),
+            Object::Integer(i) => write!(f, "{}", i),
             Object::String(s) => write!(f, "{}", s),
-            Object::NONE => write!(f, "null"),
-            Object::Callable(fun) => write!(f, "Object callable function: {}", fun.name.lexeme),
+            Object::NONE => write!(f, "nil"),
+            Object::Callable(fun) => write!(f, "<fn {}>", fun.name.lexeme),
+            Object::NativeFunction(fun) => write!(f, "{}", fun),
+            Object::List(list) => {
+                let pointer = Rc::as_ptr(list) as *const ();
+                if parents.contains(&pointer) {
+                    return write!(f, "[...]");
+                }
+                parents.push(pointer);
+                write!(f, "[")?;
+                for (index, element) in list.borrow().iter().enumerate() {
+                    if index > 0 {
+                        write!(f, ", ")?;
+                    }
+                    element.write(f, parents)?;
+                }
+                parents.pop();
+                write!(f, "]")
+            }
+            Object::Map(map) => {
+                let pointer = Rc::as_ptr(map) as *const ();
+                if parents.contains(&pointer) {
+                    return write!(f, "{{...}}");
+                }
+                parents.push(pointer);
+                write!(f, "{{")?;
+                for (index, (key, value)) in map.borrow().iter().enumerate() {
+                    if index > 0 {
+                        write!(f, ", ")?;
+                    }
+                    write!(f, "{}: ", key)?;
+                    value.write(f, parents)?;
+                }
+                parents.pop();
+                write!(f, "}}")
+            }
+            Object::Range(range) => write!(f, "{}", range),
         }
     }
 }
 
+impl fmt::Display for Object {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.write(f, &mut Vec::new())
+    }
+}
+
 pub struct Interpreter {
     environment: Rc<RefCell<Environment>>,
+    // Turns off the natives that reach outside the interpreter, files and stdin.
+    sandboxed: bool,
+    regex_cache: RegexCache,
+    rng: Rng,
+    // When the interpreter was made, what elapsed() counts from.
+    started: Instant,
+    // Checked on every loop iteration and call, the script stops once it has passed.
+    deadline: Option<Instant>,
 }
 
 impl Interpreter {
     pub fn new() -> Self {
+        let mut globals = Environment::new();
+        stdlib::define_natives(&mut globals).expect("Failed to define native functions.");
+
         Interpreter {
-            environment: Rc::new(RefCell::new(Environment::new())),
+            environment: Rc::new(RefCell::new(globals)),
+            sandboxed: false,
+            regex_cache: RegexCache::default(),
+            rng: Rng::from_time(),
+            started: Instant::now(),
+            deadline: None,
         }
     }
 
-    #[allow(dead_code)]
-    fn stringify(&self, object: Object) -> String {
-        match object {
-            Object::NONE => "None".to_string(),
-            Object::Number(n) => n.to_string(),
-            Object::Boolean(b) => b.to_string(),
-            Object::String(s) => s,
-            Object::Callable(f) => f.to_string(),
+    pub fn set_sandboxed(&mut self, sandboxed: bool) {
+        self.sandboxed = sandboxed;
+    }
+
+    pub fn is_sandboxed(&self) -> bool {
+        self.sandboxed
+    }
+
+    pub fn regex_cache(&mut self) -> &mut RegexCache {
+        &mut self.regex_cache
+    }
+
+    pub fn rng(&mut self) -> &mut Rng {
+        &mut self.rng
+    }
+
+    // Defines a variable in the global scope, for things the host hands to the script like `args`.
+    pub fn define_global(&mut self, name: &str, value: Object) -> Result<(), LoxError> {
+        self.environment.borrow_mut().define_builtin(name, value)
+    }
+
+    pub fn started(&self) -> Instant {
+        self.started
+    }
+
+    pub fn set_deadline(&mut self, deadline: Option<Instant>) {
+        self.deadline = deadline;
+    }
+
+    pub fn deadline(&self) -> Option<Instant> {
+        self.deadline
+    }
+
+    // NOTE: Loops and calls are the only ways a script can keep running for long, so those are
+    // the only places this gets checked.
+    fn check_deadline(&self) -> Result<(), LoxError> {
+        match self.deadline {
+            Some(deadline) if Instant::now() >= deadline => Err(LoxError::Timeout),
+            _ => Ok(()),
         }
     }
 
+    // NOTE: Print, str() and values nested inside lists and maps all go through Display, so a
+    // value looks the same wherever it shows up.
+    fn stringify(&self, object: Object) -> String {
+        object.to_string()
+    }
+
     pub fn interpret(&mut self, statements: Vec<Stmt>) -> Result<(), LoxError> {
         for stmt in statements {
             self.execute(&stmt)?
@@ -73,11 +228,11 @@ impl Interpreter {
         Ok(())
     }
 
-    fn evaluate(&mut self, expression: &Expr) -> Result<Object, LoxError> {
+    pub fn evaluate(&mut self, expression: &Expr) -> Result<Object, LoxError> {
         expression.accept(self)
     }
 
-    fn is_truthly(&self, right: &Object) -> bool {
+    pub fn is_truthly(&self, right: &Object) -> bool {
         match right {
             Object::NONE => false,
             Object::Boolean(bool) => *bool,
@@ -98,14 +253,538 @@ impl Interpreter {
         })
     }
 
+    /// Applies an arithmetic operator. Two integers stay integers and report overflow, any other
+    /// mix of numbers is promoted to a float.
+    fn arithmetic(
+        &self,
+        operator: &Token,
+        l: &Object,
+        r: &Object,
+        integer_operation: fn(i64, i64) -> Option<i64>,
+        float_operation: fn(f64, f64) -> f64,
+    ) -> Result<Object, LoxError> {
+        if let (Object::Integer(l_integer), Object::Integer(r_integer)) = (l, r) {
+            return match integer_operation(*l_integer, *r_integer) {
+                Some(value) => Ok(Object::Integer(value)),
+                None => self.number_operand_error(operator, "Integer overflow.".to_string()),
+            };
+        }
+
+        match (l.as_float(), r.as_float()) {
+            (Some(l_number), Some(r_number)) => {
+                Ok(Object::Number(float_operation(l_number, r_number)))
+            }
+            _ => self.number_operand_error(operator, String::new()),
+        }
+    }
+
+    fn compare(
+        &self,
+        operator: &Token,
+        l: &Object,
+        r: &Object,
+        predicate: fn(Ordering) -> bool,
+    ) -> Result<Object, LoxError> {
+        let ordering = match (l, r) {
+            (Object::Integer(l_integer), Object::Integer(r_integer)) => {
+                Some(l_integer.cmp(r_integer))
+            }
+            (Object::String(l_string), Object::String(r_string)) => Some(l_string.cmp(r_string)),
+            _ => match (l.as_float(), r.as_float()) {
+                (Some(l_number), Some(r_number)) => l_number.partial_cmp(&r_number),
+                _ => return self.number_operand_error(operator, String::new()),
+            },
+        };
+
+        // NOTE: NaN is not ordered against anything, so every comparison with it is false.
+        Ok(Object::Boolean(ordering.is_some_and(predicate)))
+    }
+
+    /// Evaluates a binary operator over two already evaluated operands.
+    pub fn binary_operation(
+        &self,
+        l: Object,
+        operator: &Token,
+        r: Object,
+    ) -> Result<Object, LoxError> {
+        match operator.token_type {
+            TokenType::BANG_EQUAL => Ok(Object::Boolean(!l.equals(&r))),
+            TokenType::EQUAL_EQUAL => Ok(Object::Boolean(l.equals(&r))),
+            TokenType::LESS => self.compare(operator, &l, &r, Ordering::is_lt),
+            TokenType::LESS_EQUAL => self.compare(operator, &l, &r, Ordering::is_le),
+            TokenType::GREATER => self.compare(operator, &l, &r, Ordering::is_gt),
+            TokenType::GREATER_EQUAL => self.compare(operator, &l, &r, Ordering::is_ge),
+            TokenType::PLUS => match (l, r) {
+                (Object::String(l_string), Object::String(r_string)) => {
+                    Ok(Object::String(l_string + &r_string))
+                }
+                (l, r) => self.arithmetic(operator, &l, &r, i64::checked_add, |l, r| l + r),
+            },
+            TokenType::MINUS => self.arithmetic(operator, &l, &r, i64::checked_sub, |l, r| l - r),
+            TokenType::STAR => self.arithmetic(operator, &l, &r, i64::checked_mul, |l, r| l * r),
+            // NOTE: Division always produces a float, 3 / 2 is 1.5 no matter the operand types.
+            TokenType::SLASH => match (l.as_float(), r.as_float()) {
+                (Some(l_number), Some(r_number)) => {
+                    if r_number == 0.0 {
+                        return self.number_operand_error(operator, "Divide by zero.".to_string());
+                    }
+                    Ok(Object::Number(l_number / r_number))
+                }
+                _ => self.number_operand_error(operator, String::new()),
+            },
+            TokenType::TILDE_SLASH => {
+                if r.as_float() == Some(0.0) {
+                    return self.number_operand_error(operator, "Divide by zero.".to_string());
+                }
+                self.arithmetic(operator, &l, &r, i64::checked_div, |l, r| (l / r).trunc())
+            }
+            TokenType::PERCENT => {
+                if r.as_float() == Some(0.0) {
+                    return self.number_operand_error(operator, "Modulo by zero.".to_string());
+                }
+                // NOTE: i64::MIN % -1 overflows in the division but the remainder is just 0.
+                self.arithmetic(
+                    operator,
+                    &l,
+                    &r,
+                    |l, r| Some(l.wrapping_rem(r)),
+                    |l, r| l % r,
+                )
+            }
+            TokenType::STAR_STAR => match (l, r) {
+                // NOTE: A negative exponent can't stay an integer, so it falls through to floats.
+                (Object::Integer(base), Object::Integer(exponent)) if exponent >= 0 => {
+                    match u32::try_from(exponent)
+                        .ok()
+                        .and_then(|exponent| base.checked_pow(exponent))
+                    {
+                        Some(value) => Ok(Object::Integer(value)),
+                        None => {
+                            self.number_operand_error(operator, "Integer overflow.".to_string())
+                        }
+                    }
+                }
+                (l, r) => match (l.as_float(), r.as_float()) {
+                    (Some(base), Some(exponent)) => Ok(Object::Number(base.powf(exponent))),
+                    _ => self.number_operand_error(operator, String::new()),
+                },
+            },
+            TokenType::AMPERSAND => self.bitwise(operator, &l, &r, |l, r| Some(l & r)),
+            TokenType::PIPE => self.bitwise(operator, &l, &r, |l, r| Some(l | r)),
+            TokenType::CARET => self.bitwise(operator, &l, &r, |l, r| Some(l ^ r)),
+            TokenType::LESS_LESS => self.bitwise(operator, &l, &r, |l, r| {
+                u32::try_from(r).ok().and_then(|r| l.checked_shl(r))
+            }),
+            TokenType::GREATER_GREATER => self.bitwise(operator, &l, &r, |l, r| {
+                u32::try_from(r).ok().and_then(|r| l.checked_shr(r))
+            }),
+            _ => self.number_operand_error(operator, String::new()),
+        }
+    }
+
+    // Bitwise operators only make sense on integers, there's no promotion to floats here.
+    fn bitwise(
+        &self,
+        operator: &Token,
+        l: &Object,
+        r: &Object,
+        operation: fn(i64, i64) -> Option<i64>,
+    ) -> Result<Object, LoxError> {
+        match (l, r) {
+            (Object::Integer(l_integer), Object::Integer(r_integer)) => {
+                match operation(*l_integer, *r_integer) {
+                    Some(value) => Ok(Object::Integer(value)),
+                    None => self.number_operand_error(
+                        operator,
+                        "Shift amount must be between 0 and 63.".to_string(),
+                    ),
+                }
+            }
+            _ => self.number_operand_error(operator, "Operands must be integers.".to_string()),
+        }
+    }
+
+    // Maps `+=`, `++` and friends to the binary operator they apply. The lexeme is kept as is so
+    // errors still point at what the user wrote.
+    fn compound_operator(operator: &Token) -> Token {
+        let token_type = match operator.token_type {
+            TokenType::PLUS_EQUAL | TokenType::PLUS_PLUS => TokenType::PLUS,
+            TokenType::MINUS_EQUAL | TokenType::MINUS_MINUS => TokenType::MINUS,
+            TokenType::STAR_EQUAL => TokenType::STAR,
+            TokenType::SLASH_EQUAL => TokenType::SLASH,
+            ref other => other.clone(),
+        };
+
+        Token {
+            token_type,
+            ..operator.clone()
+        }
+    }
+
+    // Reads the target, applies the operator and writes the result back. The target is only
+    // evaluated once, so `a[f()] += 1` calls f a single time.
+    fn update_target(
+        &mut self,
+        target: &Expr,
+        operator: &Token,
+        value: Option<&Expr>,
+    ) -> Result<(Object, Object), LoxError> {
+        let binary_operator = Self::compound_operator(operator);
+
+        match target {
+            Expr::Variable { name } => {
+                let current = self.environment.borrow().get(name)?;
+                let value = match value {
+                    Some(value) => self.evaluate(value)?,
+                    None => Object::Integer(1),
+                };
+                let updated = self.binary_operation(current.clone(), &binary_operator, value)?;
+                self.environment
+                    .borrow_mut()
+                    .assign(name, updated.clone())?;
+                Ok((current, updated))
+            }
+            Expr::Index {
+                object,
+                bracket,
+                index,
+            } => {
+                let object = self.evaluate(object)?;
+                let index = self.evaluate(index)?;
+                let current = self.index_get(&object, bracket, &index)?;
+                let value = match value {
+                    Some(value) => self.evaluate(value)?,
+                    None => Object::Integer(1),
+                };
+                let updated = self.binary_operation(current.clone(), &binary_operator, value)?;
+                self.index_set(&object, bracket, &index, updated.clone())?;
+                Ok((current, updated))
+            }
+            _ => Err(LoxError::Runtime {
+                token: operator.clone(),
+                message: "Invalid assignment target.".to_string(),
+            }),
+        }
+    }
+
+    fn call_value(
+        &mut self,
+        callee_evaluated: Object,
+        paren: &Token,
+        arguments: &Vec<Argument>,
+    ) -> Result<Object, LoxError> {
+        // TODO: Fk I'll have to check itreaters in more detail, don't know shit about them. That
+        // and closures I think.
+        let mut args = Vec::new();
+        let mut named = Vec::new();
+        for argument in arguments {
+            let value = self.evaluate(&argument.value)?;
+            match &argument.name {
+                Some(name) => named.push((name.clone(), value)),
+                None => args.push(value),
+            }
+        }
+
+        self.call_object(callee_evaluated, paren, args, named)
+    }
+
+    pub fn call_object(
+        &mut self,
+        callee: Object,
+        paren: &Token,
+        args: Vec<Object>,
+        named: Vec<(Token, Object)>,
+    ) -> Result<Object, LoxError> {
+        self.check_deadline()?;
+        match callee {
+            Object::Callable(function) => function.call(self, paren, args, named),
+            Object::NativeFunction(function) => {
+                if let Some((name, _)) = named.into_iter().next() {
+                    Err(LoxError::Runtime {
+                        token: name,
+                        message: format!(
+                            "Native function '{}' doesn't take keyword arguments.",
+                            function.name
+                        ),
+                    })
+                } else if args.len() < function.min_arity || args.len() > function.max_arity {
+                    Err(arity_error(
+                        paren,
+                        function.min_arity,
+                        (function.max_arity != VARIADIC).then_some(function.max_arity),
+                        args.len(),
+                    ))
+                } else {
+                    function.call(self, paren, &args)
+                }
+            }
+            _ => Err(LoxError::Runtime {
+                token: paren.clone(),
+                message: "Can only call functions.".to_string(),
+            }),
+        }
+    }
+
+    // Evaluates the receiver of a property access or a call. None means an optional access
+    // somewhere down the chain hit nil, which makes `a?.b.c()` nil as a whole instead of failing
+    // on `.c`.
+    fn evaluate_chain(&mut self, expression: &Expr) -> Result<Option<Object>, LoxError> {
+        match expression {
+            Expr::Get {
+                object,
+                name,
+                optional,
+            } => {
+                let Some(object) = self.evaluate_chain(object)? else {
+                    return Ok(None);
+                };
+                if *optional && matches!(object, Object::NONE) {
+                    return Ok(None);
+                }
+                self.get_property(&object, name).map(Some)
+            }
+            Expr::Call {
+                callee,
+                paren,
+                arguments,
+            } => {
+                let Some(callee) = self.evaluate_chain(callee)? else {
+                    return Ok(None);
+                };
+                self.call_value(callee, paren, arguments).map(Some)
+            }
+            _ => self.evaluate(expression).map(Some),
+        }
+    }
+
+    fn get_property(&self, object: &Object, name: &Token) -> Result<Object, LoxError> {
+        match object {
+            Object::Map(map) => map_get(&map.borrow(), &name.lexeme, name),
+            _ => Err(LoxError::Runtime {
+                token: name.clone(),
+                message: "Only maps have properties.".to_string(),
+            }),
+        }
+    }
+
+    fn list_index(
+        &self,
+        list: &[Object],
+        bracket: &Token,
+        index: &Object,
+    ) -> Result<usize, LoxError> {
+        match index {
+            Object::Integer(integer) if *integer >= 0 && (*integer as usize) < list.len() => {
+                Ok(*integer as usize)
+            }
+            Object::Integer(integer) => Err(LoxError::Runtime {
+                token: bracket.clone(),
+                message: format!(
+                    "Index {} out of range for list of length {}.",
+                    integer,
+                    list.len()
+                ),
+            }),
+            _ => Err(LoxError::Runtime {
+                token: bracket.clone(),
+                message: "List index must be an integer.".to_string(),
+            }),
+        }
+    }
+
+    fn index_get(
+        &self,
+        object: &Object,
+        bracket: &Token,
+        index: &Object,
+    ) -> Result<Object, LoxError> {
+        match object {
+            Object::List(list) => {
+                let list = list.borrow();
+                let position = self.list_index(&list, bracket, index)?;
+                Ok(list[position].clone())
+            }
+            Object::Map(map) => map_get(&map.borrow(), &map_key(bracket, index)?, bracket),
+            _ => Err(LoxError::Runtime {
+                token: bracket.clone(),
+                message: "Only lists and maps can be indexed.".to_string(),
+            }),
+        }
+    }
+
+    fn index_set(
+        &self,
+        object: &Object,
+        bracket: &Token,
+        index: &Object,
+        value: Object,
+    ) -> Result<(), LoxError> {
+        match object {
+            Object::List(list) => {
+                let mut list = list.borrow_mut();
+                let position = self.list_index(&list, bracket, index)?;
+                list[position] = value;
+                Ok(())
+            }
+            Object::Map(map) => {
+                map.borrow_mut().insert(map_key(bracket, index)?, value);
+                Ok(())
+            }
+            _ => Err(LoxError::Runtime {
+                token: bracket.clone(),
+                message: "Only lists and maps can be indexed.".to_string(),
+            }),
+        }
+    }
+
+    // The iteration protocol behind `for (x in ...)`. Strings give back their characters, lists
+    // their elements, ranges their integers and a function is called until it returns nil.
+    fn iterator(&self, iterable: Object, token: &Token) -> Result<LoxIterator, LoxError> {
+        match iterable {
+            Object::String(string) => Ok(LoxIterator::Values(
+                string
+                    .chars()
+                    .map(|character| Object::String(character.to_string()))
+                    .collect::<Vec<Object>>()
+                    .into_iter(),
+            )),
+            // NOTE: We iterate over a snapshot so changing the list in the body doesn't trip us.
+            Object::List(list) => Ok(LoxIterator::Values(list.borrow().clone().into_iter())),
+            Object::Map(map) => Ok(LoxIterator::Values(
+                map.borrow()
+                    .keys()
+                    .map(|key| Object::String(key.clone()))
+                    .collect::<Vec<Object>>()
+                    .into_iter(),
+            )),
+            Object::Range(range) => Ok(LoxIterator::Range(range)),
+            function @ (Object::Callable(_) | Object::NativeFunction(_)) => {
+                Ok(LoxIterator::Function(function))
+            }
+            _ => Err(LoxError::Runtime {
+                token: token.clone(),
+                message: "Can only iterate over strings, lists, maps, ranges and functions."
+                    .to_string(),
+            }),
+        }
+    }
+
+    // Decides what a loop does after running its body once. A break or continue aimed at another
+    // (outer) loop by its label keeps unwinding.
+    fn loop_flow(
+        &self,
+        body_execution_result: Result<(), LoxError>,
+        label: &Option<Token>,
+    ) -> Result<LoopFlow, LoxError> {
+        let targets_this_loop = |target: &Option<String>| match target {
+            None => true,
+            Some(target) => label.as_ref().is_some_and(|label| &label.lexeme == target),
+        };
+
+        let flow = match body_execution_result {
+            Ok(()) => LoopFlow::Next,
+            Err(LoxError::BreakStmtError { label: target }) if targets_this_loop(&target) => {
+                LoopFlow::Break
+            }
+            Err(LoxError::ContinueStmtError { label: target }) if targets_this_loop(&target) => {
+                LoopFlow::Next
+            }
+            Err(other) => return Err(other),
+        };
+        if let LoopFlow::Next = flow {
+            self.check_deadline()?;
+        }
+        Ok(flow)
+    }
+
+    fn literal_value(value: &Literal) -> Object {
+        match value {
+            Literal::String(str) => Object::String(str.clone()),
+            Literal::Float(f) => Object::Number(*f),
+            Literal::Integer(i) => Object::Integer(*i),
+            Literal::None => Object::NONE,
+            Literal::Boolean(boolean) => Object::Boolean(*boolean),
+        }
+    }
+
+    fn pattern_matches(&self, pattern: &Pattern, value: &Object, keyword: &Token) -> bool {
+        match pattern {
+            Pattern::Literal(literal) => Self::literal_value(literal).equals(value),
+            // NOTE: Values that can't be compared against the bounds, i.e. a string against a
+            // number range, simply don't match.
+            Pattern::Range {
+                start,
+                end,
+                inclusive,
+            } => {
+                let is_true =
+                    |result: Result<Object, LoxError>| matches!(result, Ok(Object::Boolean(true)));
+                let end_predicate = if *inclusive {
+                    Ordering::is_le
+                } else {
+                    Ordering::is_lt
+                };
+
+                is_true(self.compare(keyword, value, &Self::literal_value(start), Ordering::is_ge))
+                    && is_true(self.compare(
+                        keyword,
+                        value,
+                        &Self::literal_value(end),
+                        end_predicate,
+                    ))
+            }
+            Pattern::Binding(_) | Pattern::Wildcard => true,
+        }
+    }
+
+    // Runs the guard and, if it passes, the body of an arm whose pattern already matched. Returns
+    // whether the arm was taken.
+    fn execute_arm(
+        &mut self,
+        arm: &MatchArm,
+        environment: Rc<RefCell<Environment>>,
+    ) -> Result<bool, LoxError> {
+        let previous = self.environment.clone();
+        self.environment = environment;
+
+        let mut execute_guarded = || -> Result<bool, LoxError> {
+            if let Some(guard) = &arm.guard {
+                let guard_value = self.evaluate(guard)?;
+                if !self.is_truthly(&guard_value) {
+                    return Ok(false);
+                }
+            }
+            self.execute(&arm.body)?;
+            Ok(true)
+        };
+
+        let result = execute_guarded();
+        self.environment = previous;
+
+        result
+    }
+
     fn execute(&mut self, stmt: &Stmt) -> Result<(), LoxError> {
         stmt.accept(self)?;
         Ok(())
     }
 
+    // Evaluates an expression as if it was written inside of the given environment.
+    pub fn evaluate_in(
+        &mut self,
+        expression: &Expr,
+        environment: Rc<RefCell<Environment>>,
+    ) -> Result<Object, LoxError> {
+        let previous = std::mem::replace(&mut self.environment, environment);
+        let result = self.evaluate(expression);
+        self.environment = previous;
+
+        result
+    }
+
     pub fn execute_block(
         &mut self,
-        statements: &Vec<Stmt>,
+        statements: &[Stmt],
         environment: Rc<RefCell<Environment>>,
     ) -> Result<(), LoxError> {
         let previous = self.environment.clone();
@@ -127,6 +806,118 @@ impl Interpreter {
     }
 }
 
+fn map_key(bracket: &Token, index: &Object) -> Result<String, LoxError> {
+    match index {
+        Object::String(key) => Ok(key.clone()),
+        _ => Err(LoxError::Runtime {
+            token: bracket.clone(),
+            message: "Map key must be a string.".to_string(),
+        }),
+    }
+}
+
+fn map_get(map: &BTreeMap<String, Object>, key: &str, token: &Token) -> Result<Object, LoxError> {
+    map.get(key).cloned().ok_or_else(|| LoxError::Runtime {
+        token: token.clone(),
+        message: format!("Map has no key '{}'.", key),
+    })
+}
+
+// Takes the value apart according to the pattern and hands every name its piece through `bind`,
+// which defines or assigns depending on where the pattern was written.
+pub fn destructure(
+    pattern: &BindingPattern,
+    value: Object,
+    bind: &mut dyn FnMut(&Token, Object) -> Result<(), LoxError>,
+) -> Result<(), LoxError> {
+    match pattern {
+        BindingPattern::Name(name) => bind(name, value),
+        BindingPattern::List {
+            bracket,
+            elements,
+            rest,
+        } => {
+            let Object::List(list) = value else {
+                return Err(LoxError::Runtime {
+                    token: bracket.clone(),
+                    message: format!("Can't destructure {} as a list.", value),
+                });
+            };
+            let mut values = list.borrow().clone();
+
+            let length_matches = match rest {
+                Some(_) => values.len() >= elements.len(),
+                None => values.len() == elements.len(),
+            };
+            if !length_matches {
+                return Err(LoxError::Runtime {
+                    token: bracket.clone(),
+                    message: format!(
+                        "Expected {}{} elements to destructure, but got {}.",
+                        if rest.is_some() { "at least " } else { "" },
+                        elements.len(),
+                        values.len()
+                    ),
+                });
+            }
+
+            let leftover = values.split_off(elements.len());
+            for (element, value) in elements.iter().zip(values) {
+                destructure(element, value, bind)?;
+            }
+            if let Some(rest) = rest {
+                bind(rest, Object::List(Rc::new(RefCell::new(leftover))))?;
+            }
+            Ok(())
+        }
+        BindingPattern::Map { brace, fields } => {
+            let Object::Map(map) = value else {
+                return Err(LoxError::Runtime {
+                    token: brace.clone(),
+                    message: format!("Can't destructure {} as a map.", value),
+                });
+            };
+
+            for (key, field) in fields {
+                let value = map_get(&map.borrow(), &key.lexeme, key)?;
+                destructure(field, value, bind)?;
+            }
+            Ok(())
+        }
+    }
+}
+
+enum LoopFlow {
+    Next,
+    Break,
+}
+
+// State of a running `for (x in ...)` loop.
+enum LoxIterator {
+    Values(std::vec::IntoIter<Object>),
+    Range(Range),
+    Function(Object),
+}
+
+impl LoxIterator {
+    fn next(
+        &mut self,
+        interpreter: &mut Interpreter,
+        token: &Token,
+    ) -> Result<Option<Object>, LoxError> {
+        match self {
+            LoxIterator::Values(values) => Ok(values.next()),
+            LoxIterator::Range(range) => Ok(range.next().map(Object::Integer)),
+            LoxIterator::Function(function) => {
+                match interpreter.call_object(function.clone(), token, Vec::new(), Vec::new())? {
+                    Object::NONE => Ok(None),
+                    value => Ok(Some(value)),
+                }
+            }
+        }
+    }
+}
+
 impl expr::Visitor<Object> for Interpreter {
     fn visit_binary_expr(
         &mut self,
@@ -137,77 +928,7 @@ impl expr::Visitor<Object> for Interpreter {
         let l = self.evaluate(left)?;
         let r = self.evaluate(right)?;
 
-        match operator.token_type {
-            TokenType::BANG_EQUAL => Ok(Object::Boolean(!l.equals(&r))),
-            TokenType::EQUAL_EQUAL => Ok(Object::Boolean(l.equals(&r))),
-            TokenType::LESS => match (l, r) {
-                (Object::Number(l_number), Object::Number(r_number)) => {
-                    Ok(Object::Boolean(l_number < r_number))
-                }
-                (Object::String(l_string), Object::String(r_string)) => {
-                    Ok(Object::Boolean(l_string < r_string))
-                }
-                _ => self.number_operand_error(operator, String::new()),
-            },
-            TokenType::LESS_EQUAL => match (l, r) {
-                (Object::Number(l_number), Object::Number(r_number)) => {
-                    Ok(Object::Boolean(l_number <= r_number))
-                }
-                (Object::String(l_string), Object::String(r_string)) => {
-                    Ok(Object::Boolean(l_string <= r_string))
-                }
-                _ => self.number_operand_error(operator, String::new()),
-            },
-            TokenType::GREATER => match (l, r) {
-                (Object::Number(l_number), Object::Number(r_number)) => {
-                    Ok(Object::Boolean(l_number > r_number))
-                }
-                (Object::String(l_string), Object::String(r_string)) => {
-                    Ok(Object::Boolean(l_string > r_string))
-                }
-                _ => self.number_operand_error(operator, String::new()),
-            },
-            TokenType::GREATER_EQUAL => match (l, r) {
-                (Object::Number(l_number), Object::Number(r_number)) => {
-                    Ok(Object::Boolean(l_number >= r_number))
-                }
-                (Object::String(l_string), Object::String(r_string)) => {
-                    Ok(Object::Boolean(l_string >= r_string))
-                }
-                _ => self.number_operand_error(operator, String::new()),
-            },
-            TokenType::PLUS => match (l, r) {
-                (Object::Number(l_number), Object::Number(r_number)) => {
-                    Ok(Object::Number(l_number + r_number))
-                }
-                (Object::String(l_string), Object::String(r_string)) => {
-                    Ok(Object::String(l_string + &r_string))
-                }
-                _ => self.number_operand_error(operator, String::new()),
-            },
-            TokenType::MINUS => match (l, r) {
-                (Object::Number(l_number), Object::Number(r_number)) => {
-                    Ok(Object::Number(l_number - r_number))
-                }
-                _ => self.number_operand_error(operator, String::new()),
-            },
-            TokenType::STAR => match (l, r) {
-                (Object::Number(l_number), Object::Number(r_number)) => {
-                    Ok(Object::Number(l_number * r_number))
-                }
-                _ => self.number_operand_error(operator, String::new()),
-            },
-            TokenType::SLASH => match (l, r) {
-                (Object::Number(l_number), Object::Number(r_number)) => {
-                    if r_number == 0.0 {
-                        return self.number_operand_error(operator, "Divide by zero.".to_string());
-                    }
-                    return Ok(Object::Number(l_number / r_number));
-                }
-                _ => self.number_operand_error(operator, String::new()),
-            },
-            _ => self.number_operand_error(operator, String::new()),
-        }
+        self.binary_operation(l, operator, r)
     }
 
     fn visit_grouping_expr(&mut self, expression: &Expr) -> Result<Object, LoxError> {
@@ -215,12 +936,7 @@ impl expr::Visitor<Object> for Interpreter {
     }
 
     fn visit_literal_expr(&mut self, value: &Literal) -> Result<Object, LoxError> {
-        match value {
-            Literal::String(str) => Ok(Object::String(str.clone())),
-            Literal::Float(f) => Ok(Object::Number(*f)),
-            Literal::None => Ok(Object::NONE),
-            Literal::Boolean(boolean) => Ok(Object::Boolean(*boolean)),
-        }
+        Ok(Self::literal_value(value))
     }
 
     fn visit_unary_expr(
@@ -233,9 +949,13 @@ impl expr::Visitor<Object> for Interpreter {
         match operator.token_type {
             TokenType::MINUS => match right {
                 Object::Number(num) => Ok(Object::Number(-num)),
+                Object::Integer(integer) => match integer.checked_neg() {
+                    Some(negated) => Ok(Object::Integer(negated)),
+                    None => self.number_operand_error(operator, "Integer overflow.".to_string()),
+                },
                 _ => self.number_operand_error(operator, String::new()),
             },
-            TokenType::BANG => Ok(Object::Boolean(self.is_truthly(&right))),
+            TokenType::BANG => Ok(Object::Boolean(!self.is_truthly(&right))),
             _ => self.number_operand_error(operator, String::new()),
         }
     }
@@ -262,6 +982,11 @@ impl expr::Visitor<Object> for Interpreter {
             if self.is_truthly(&left_value) {
                 return Ok(left_value);
             }
+        } else if operator.token_type == TokenType::QUESTION_QUESTION {
+            // Unlike or, only nil falls through to the right, so `0 ?? 1` is 0.
+            if !matches!(left_value, Object::NONE) {
+                return Ok(left_value);
+            }
         } else {
             if !self.is_truthly(&left_value) {
                 return Ok(left_value);
@@ -275,37 +1000,128 @@ impl expr::Visitor<Object> for Interpreter {
         &mut self,
         callee: &Expr,
         paren: &Token,
-        arguments: &Vec<Expr>,
+        arguments: &Vec<Argument>,
     ) -> Result<Object, LoxError> {
-        // TODO: Check if the callee is in fact callable and if not throw an error.
-        let callee_evaluated = self.evaluate(callee)?;
+        match self.evaluate_chain(callee)? {
+            Some(callee_evaluated) => self.call_value(callee_evaluated, paren, arguments),
+            None => Ok(Object::NONE),
+        }
+    }
 
-        // TODO: Fk I'll have to check itreaters in more detail, don't know shit about them. That
-        // and closures I think.
-        let arguments_evaluated: Result<Vec<Object>, LoxError> = arguments
-            .into_iter()
-            .map(|expr| self.evaluate(expr))
-            .collect();
-        let args = arguments_evaluated?;
-
-        if let Object::Callable(function) = callee_evaluated {
-            if args.len() != function.arity() {
-                Err(LoxError::Runtime {
-                    token: paren.clone(),
-                    message: format!(
-                        "Expected {} arguments, but got {}.",
-                        function.arity(),
-                        args.len()
-                    ),
-                })
-            } else {
-                function.call(self, &args)
-            }
+    fn visit_compound_assign_expr(
+        &mut self,
+        target: &Expr,
+        operator: &Token,
+        value: &Expr,
+    ) -> Result<Object, LoxError> {
+        let (_, updated) = self.update_target(target, operator, Some(value))?;
+        Ok(updated)
+    }
+
+    fn visit_conditional_expr(
+        &mut self,
+        condition: &Expr,
+        then_branch: &Expr,
+        else_branch: &Expr,
+    ) -> Result<Object, LoxError> {
+        let condition_value = self.evaluate(condition)?;
+        if self.is_truthly(&condition_value) {
+            self.evaluate(then_branch)
         } else {
-            Err(LoxError::Runtime {
-                token: paren.clone(),
-                message: "Can only call functions.".to_string(),
-            })
+            self.evaluate(else_branch)
+        }
+    }
+
+    fn visit_get_expr(
+        &mut self,
+        object: &Expr,
+        name: &Token,
+        optional: bool,
+    ) -> Result<Object, LoxError> {
+        let Some(object) = self.evaluate_chain(object)? else {
+            return Ok(Object::NONE);
+        };
+        if optional && matches!(object, Object::NONE) {
+            return Ok(Object::NONE);
+        }
+        self.get_property(&object, name)
+    }
+
+    fn visit_list_expr(&mut self, elements: &Vec<Expr>) -> Result<Object, LoxError> {
+        let mut values = Vec::with_capacity(elements.len());
+        for element in elements {
+            values.push(self.evaluate(element)?);
+        }
+        Ok(Object::List(Rc::new(RefCell::new(values))))
+    }
+
+    fn visit_index_expr(
+        &mut self,
+        object: &Expr,
+        bracket: &Token,
+        index: &Expr,
+    ) -> Result<Object, LoxError> {
+        let object = self.evaluate(object)?;
+        let index = self.evaluate(index)?;
+        self.index_get(&object, bracket, &index)
+    }
+
+    fn visit_set_index_expr(
+        &mut self,
+        object: &Expr,
+        bracket: &Token,
+        index: &Expr,
+        value: &Expr,
+    ) -> Result<Object, LoxError> {
+        let object = self.evaluate(object)?;
+        let index = self.evaluate(index)?;
+        let value = self.evaluate(value)?;
+        self.index_set(&object, bracket, &index, value.clone())?;
+        Ok(value)
+    }
+
+    fn visit_map_expr(
+        &mut self,
+        _brace: &Token,
+        entries: &Vec<(Token, Expr)>,
+    ) -> Result<Object, LoxError> {
+        let mut map = BTreeMap::new();
+        for (key, value) in entries {
+            // NOTE: String keys come with their quotes in the lexeme, the literal doesn't.
+            let key = match &key.literal {
+                Literal::String(key) => key.clone(),
+                _ => key.lexeme.clone(),
+            };
+            map.insert(key, self.evaluate(value)?);
+        }
+        Ok(Object::Map(Rc::new(RefCell::new(map))))
+    }
+
+    fn visit_destructure_assign_expr(
+        &mut self,
+        pattern: &BindingPattern,
+        _equals: &Token,
+        value: &Expr,
+    ) -> Result<Object, LoxError> {
+        let value = self.evaluate(value)?;
+        let environment = Rc::clone(&self.environment);
+        destructure(pattern, value.clone(), &mut |name, value| {
+            environment.borrow_mut().assign(name, value)
+        })?;
+        Ok(value)
+    }
+
+    fn visit_increment_expr(
+        &mut self,
+        target: &Expr,
+        operator: &Token,
+        prefix: bool,
+    ) -> Result<Object, LoxError> {
+        let (previous, updated) = self.update_target(target, operator, None)?;
+        if prefix {
+            Ok(updated)
+        } else {
+            Ok(previous)
         }
     }
 }
@@ -331,26 +1147,35 @@ impl stmt::Visitor<()> for Interpreter {
 
     fn visit_print_stmt(&mut self, expression: &Expr) -> Result<(), LoxError> {
         let value = self.evaluate(expression)?;
-        println!("Visiting Print Statement: {}", self.stringify(value));
+        println!("{}", self.stringify(value));
         Ok(())
     }
 
-    fn visit_var_stmt(&mut self, name: &Token, initializer: &Option<Expr>) -> Result<(), LoxError> {
-        match initializer {
-            Some(init) => {
-                let value = self.evaluate(init)?;
-                self.environment
-                    .borrow_mut()
-                    .define(name.lexeme.clone(), value)?;
-                return Ok(());
-            }
-            None => {
-                self.environment
-                    .borrow_mut()
-                    .define(name.lexeme.clone(), Object::NONE)?;
-                return Ok(());
-            }
-        }
+    fn visit_var_stmt(
+        &mut self,
+        target: &BindingPattern,
+        _annotation: &Option<TypeAnnotation>,
+        initializer: &Option<Expr>,
+    ) -> Result<(), LoxError> {
+        let value = match initializer {
+            Some(init) => self.evaluate(init)?,
+            None => Object::NONE,
+        };
+
+        let environment = Rc::clone(&self.environment);
+        destructure(target, value, &mut |name, value| {
+            environment.borrow_mut().define(name, value)
+        })
+    }
+
+    fn visit_const_stmt(
+        &mut self,
+        name: &Token,
+        _annotation: &Option<TypeAnnotation>,
+        initializer: &Expr,
+    ) -> Result<(), LoxError> {
+        let value = self.evaluate(initializer)?;
+        self.environment.borrow_mut().define_constant(name, value)
     }
 
     fn visit_if_statement(
@@ -373,6 +1198,8 @@ impl stmt::Visitor<()> for Interpreter {
         &mut self,
         condition: &Expr,
         body: &Box<Stmt>,
+        increment: &Option<Expr>,
+        label: &Option<Token>,
     ) -> Result<(), LoxError> {
         // NOTE: Once again if you're thinking of using something like
         // `self.is_trutly(&self.evalutate(condition)?)`
@@ -381,46 +1208,136 @@ impl stmt::Visitor<()> for Interpreter {
         // You see assigning the value ends the borrow as we surrender the ownership to the local
         // variable, but directly calling it withing the function does not.
         let mut condition_value = self.evaluate(condition)?;
-        self.environment.borrow_mut().is_enclosed_in_loop = true;
         while self.is_truthly(&condition_value) {
             let body_execution_result = self.execute(body);
-            if let Err(LoxError::BreakStmtError) = body_execution_result {
+            if let LoopFlow::Break = self.loop_flow(body_execution_result, label)? {
                 break;
-            } else if let Err(LoxError::Return { value }) = body_execution_result {
-                return Err(LoxError::Return { value });
-            } else if let Err(LoxError::ContinueStmtError) = body_execution_result {
-                condition_value = self.evaluate(condition)?;
-                continue;
-            } else {
-                condition_value = self.evaluate(condition)?;
             }
+
+            if let Some(increment) = increment {
+                self.evaluate(increment)?;
+            }
+            condition_value = self.evaluate(condition)?;
         }
 
-        // This most certainly would have performance overheads, but I can't think of anything
-        // more graceful.
-        self.environment.borrow_mut().is_enclosed_in_loop = false;
+        Ok(())
+    }
+
+    fn visit_for_in_stmt(
+        &mut self,
+        variable: &Token,
+        iterable: &Expr,
+        body: &Stmt,
+        label: &Option<Token>,
+    ) -> Result<(), LoxError> {
+        let iterable_value = self.evaluate(iterable)?;
+        let mut iterator = self.iterator(iterable_value, variable)?;
+
+        while let Some(value) = iterator.next(self, variable)? {
+            // A fresh environment each time around, so closures capture that iteration's value.
+            let environment = Rc::new(RefCell::new(Environment::create_enclosing_for_env(
+                &self.environment,
+            )));
+            environment.borrow_mut().define(variable, value)?;
+
+            let body_execution_result = self.execute_block(std::slice::from_ref(body), environment);
+            if let LoopFlow::Break = self.loop_flow(body_execution_result, label)? {
+                break;
+            }
+        }
 
         Ok(())
     }
 
-    fn visit_break_stmt(&mut self) -> Result<(), LoxError> {
-        if self.environment.borrow().is_enclosed_in_loop {
-            return Err(LoxError::BreakStmtError);
+    fn visit_do_while_stmt(
+        &mut self,
+        body: &Stmt,
+        condition: &Expr,
+        label: &Option<Token>,
+    ) -> Result<(), LoxError> {
+        loop {
+            let body_execution_result = self.execute(body);
+            if let LoopFlow::Break = self.loop_flow(body_execution_result, label)? {
+                break;
+            }
+
+            let condition_value = self.evaluate(condition)?;
+            if !self.is_truthly(&condition_value) {
+                break;
+            }
         }
-        return Ok(());
+
+        Ok(())
     }
 
-    fn visit_continue_stmt(&mut self) -> Result<(), LoxError> {
-        if self.environment.borrow().is_enclosed_in_loop {
-            return Err(LoxError::ContinueStmtError);
+    fn visit_loop_stmt(&mut self, body: &Stmt, label: &Option<Token>) -> Result<(), LoxError> {
+        loop {
+            let body_execution_result = self.execute(body);
+            if let LoopFlow::Break = self.loop_flow(body_execution_result, label)? {
+                return Ok(());
+            }
         }
-        return Ok(());
+    }
+
+    fn visit_match_stmt(
+        &mut self,
+        keyword: &Token,
+        subject: &Expr,
+        arms: &Vec<MatchArm>,
+    ) -> Result<(), LoxError> {
+        let value = self.evaluate(subject)?;
+
+        for arm in arms {
+            let Some(pattern) = arm
+                .patterns
+                .iter()
+                .find(|pattern| self.pattern_matches(pattern, &value, keyword))
+            else {
+                continue;
+            };
+
+            // Each arm gets its own environment, bindings and anything declared in the body
+            // stay in there.
+            let environment = Rc::new(RefCell::new(Environment::create_enclosing_for_env(
+                &self.environment,
+            )));
+            if let Pattern::Binding(name) = pattern {
+                environment.borrow_mut().define(name, value.clone())?;
+            }
+
+            if self.execute_arm(arm, environment)? {
+                break;
+            }
+        }
+
+        Ok(())
+    }
+
+    fn visit_break_stmt(
+        &mut self,
+        _keyword: &Token,
+        label: &Option<Token>,
+    ) -> Result<(), LoxError> {
+        Err(LoxError::BreakStmtError {
+            label: label.as_ref().map(|label| label.lexeme.clone()),
+        })
+    }
+
+    fn visit_continue_stmt(
+        &mut self,
+        _keyword: &Token,
+        label: &Option<Token>,
+    ) -> Result<(), LoxError> {
+        Err(LoxError::ContinueStmtError {
+            label: label.as_ref().map(|label| label.lexeme.clone()),
+        })
     }
 
     fn visit_function_stmt(
         &mut self,
         name: &Token,
-        paramaters: &Vec<Token>,
+        paramaters: &Vec<Parameter>,
+        _return_type: &Option<TypeAnnotation>,
         body: &Vec<Stmt>,
     ) -> Result<(), LoxError> {
         let function = Function {
@@ -432,7 +1349,7 @@ impl stmt::Visitor<()> for Interpreter {
 
         self.environment
             .borrow_mut()
-            .define(name.lexeme.clone(), Object::Callable(function))?;
+            .define(name, Object::Callable(function))?;
         Ok(())
     }
 
@@ -453,3 +1370,350 @@ impl stmt::Visitor<()> for Interpreter {
         })
     }
 }
+
+// Runs every statement but the last and evaluates the last one, which must be an expression.
+#[cfg(test)]
+fn evaluate_source(source: &str) -> Result<Object, LoxError> {
+    use crate::lox_interpreter::{parser::Parser, scanner::Scanner};
+
+    let tokens = Scanner::new(source.into()).scan_tokens()?;
+    let mut statements = Parser::new(tokens).parse()?;
+    let Some(Stmt::Expression { expression }) = statements.pop() else {
+        panic!("Expected the source to end with an expression statement.");
+    };
+
+    let mut interpreter = Interpreter::new();
+    interpreter.interpret(statements)?;
+    interpreter.evaluate(&expression)
+}
+
+#[test]
+fn test_compound_assignment_and_increments() {
+    let evaluate = |source: &str| evaluate_source(source).unwrap().to_string();
+
+    assert_eq!(
+        evaluate("var x = 10; x += 5; x -= 3; x *= 2; x /= 4; x;"),
+        "6"
+    );
+    assert_eq!(evaluate("var x = 1; var old = x++; old * 10 + x;"), "12");
+    assert_eq!(evaluate("var x = 1; var new = ++x; new * 10 + x;"), "22");
+    assert_eq!(evaluate("var x = 3; x-- + --x;"), "4");
+    assert_eq!(
+        evaluate("var x = 1; [x++, x, ++x, x--, --x];"),
+        "[1, 2, 3, 3, 1]"
+    );
+
+    // The object and index of an indexed target are evaluated once.
+    assert_eq!(
+        evaluate(
+            "var calls = 0; fun at() { calls += 1; return 0; } var l = [5]; \
+             l[at()] += 2; l[at()]++; [l[0], calls];"
+        ),
+        "[8, 2]"
+    );
+}
+
+#[test]
+fn test_do_while_and_loop() {
+    let evaluate = |source: &str| evaluate_source(source).unwrap().to_string();
+
+    assert_eq!(
+        evaluate("var runs = 0; do { runs += 1; } while (false); runs;"),
+        "1"
+    );
+    assert_eq!(
+        evaluate("var i = 0; loop { i += 1; if (i == 3) break; } i;"),
+        "3"
+    );
+}
+
+#[test]
+fn test_bang_negates_truthiness() {
+    let evaluate = |source: &str| evaluate_source(source).unwrap().to_string();
+
+    assert_eq!(evaluate("!true == false;"), "true");
+    assert_eq!(evaluate("!nil == true;"), "true");
+    assert_eq!(evaluate("!0;"), "false");
+    assert_eq!(evaluate("!!\"\";"), "true");
+}
+
+#[test]
+fn test_functions_display_their_name() {
+    let evaluate = |source: &str| evaluate_source(source).unwrap().to_string();
+
+    assert_eq!(evaluate("fun add(a, b) { return a + b; } add;"), "<fn add>");
+    assert_eq!(evaluate("fun f() {} str([f]);"), "[<fn f>]");
+}
+
+#[test]
+fn test_integer_remainder() {
+    let evaluate = |source: &str| evaluate_source(source).unwrap().to_string();
+
+    assert_eq!(evaluate("-7 % 3;"), "-1");
+    assert_eq!(evaluate("(-9223372036854775807 - 1) % -1;"), "0");
+    match evaluate_source("1 % 0;") {
+        Err(LoxError::Runtime { message, .. }) => assert_eq!(message, "Modulo by zero."),
+        other => panic!("Expected a runtime error, got {:?}", other),
+    }
+}
+
+#[test]
+fn test_redeclaring_constant_fails_at_runtime() {
+    let message = |source: &str| match evaluate_source(source) {
+        Err(LoxError::Runtime { message, .. }) => message,
+        other => panic!("Expected a runtime error, got {:?}", other),
+    };
+
+    // The resolver catches these before they run, this is the interpreter's own check.
+    assert_eq!(
+        message("const PORT = 80; var PORT = 8080; PORT;"),
+        "Cannot redeclare constant 'PORT'."
+    );
+    assert_eq!(
+        message("{ const PORT = 80; fun PORT() {} } nil;"),
+        "Cannot redeclare constant 'PORT'."
+    );
+
+    // The built-in PI and E aren't constants, scripts can take the names.
+    assert_eq!(evaluate_source("var PI = 3; PI;").unwrap().to_string(), "3");
+    assert_eq!(
+        evaluate_source("fun E() {} E;").unwrap().to_string(),
+        "<fn E>"
+    );
+}
+
+#[test]
+fn test_conditional_and_nil_operators() {
+    let evaluate = |source: &str| evaluate_source(source).unwrap().to_string();
+
+    // `?:` groups to the right, the else branch holds the second conditional.
+    assert_eq!(evaluate("false ? 1 : true ? 2 : 3;"), "2");
+    assert_eq!(evaluate("true ? 1 : false ? 2 : 3;"), "1");
+    assert_eq!(evaluate("false ? 1 : false ? 2 : 3;"), "3");
+
+    assert_eq!(evaluate("0 ?? 1;"), "0");
+    assert_eq!(evaluate("false ?? 1;"), "false");
+    assert_eq!(evaluate("nil ?? 1;"), "1");
+
+    // The whole chain is nil, `.c` isn't looked up on the nil from `a?.b`.
+    assert_eq!(evaluate("var a = nil; a?.b.c;"), "nil");
+    assert_eq!(evaluate("var a = {b: {c: 1}}; a?.b.c;"), "1");
+    assert!(evaluate_source("var a = {b: nil}; a?.b.c;").is_err());
+}
+
+#[test]
+fn test_self_containing_structures() {
+    let evaluate = |source: &str| evaluate_source(source).unwrap().to_string();
+
+    // NOTE: The cycles are broken at the end so the lists are freed.
+    assert_eq!(
+        evaluate("var a = [1]; a[0] = a; var s = str(a); a[0] = nil; s;"),
+        "[[...]]"
+    );
+    assert_eq!(
+        evaluate("var m = {x: 1}; m[\"self\"] = m; var s = str([m]); m[\"self\"] = nil; s;"),
+        "[{self: {...}, x: 1}]"
+    );
+    // Shared but not cyclic, printed in full both times.
+    assert_eq!(evaluate("var a = [1]; [a, a];"), "[[1], [1]]");
+
+    assert_eq!(
+        evaluate(
+            "var a = [1]; a[0] = a; var b = [1]; b[0] = b; var same = a == b; \
+             a[0] = nil; b[0] = nil; same;"
+        ),
+        "true"
+    );
+    assert_eq!(
+        evaluate(
+            "var a = [1, 2]; a[0] = a; var b = [1, 3]; b[0] = b; var same = a == b; \
+             a[0] = nil; b[0] = nil; same;"
+        ),
+        "false"
+    );
+}
+
+#[test]
+fn test_labelled_break_and_continue() {
+    let evaluate = |source: &str| evaluate_source(source).unwrap().to_string();
+
+    assert_eq!(
+        evaluate(
+            "var seen = \"\"; \
+             outer: for (var i = 0; i < 3; i++) { \
+                 for (var j = 0; j < 3; j++) { \
+                     if (j == 1) continue outer; \
+                     if (i == 2) break outer; \
+                     seen += str(i) + str(j) + \" \"; \
+                 } \
+             } seen;"
+        ),
+        "00 10 "
+    );
+    assert_eq!(
+        evaluate(
+            "var inner = 0; var outer_runs = 0; \
+             outer: while (outer_runs < 3) { \
+                 outer_runs += 1; \
+                 while (true) { inner += 1; break outer; } \
+             } [outer_runs, inner];"
+        ),
+        "[1, 1]"
+    );
+
+    // The increment still runs, otherwise the loop would never end.
+    assert_eq!(
+        evaluate(
+            "var odd = 0; for (var i = 0; i < 5; i++) { if (i % 2 == 0) continue; odd += 1; } odd;"
+        ),
+        "2"
+    );
+    assert_eq!(
+        evaluate(
+            "var count = 0; \
+             outer: for (var i = 0; i < 3; i++) { for (;;) { count += 1; continue outer; } } count;"
+        ),
+        "3"
+    );
+}
+
+#[test]
+fn test_match_statement() {
+    let evaluate = |source: &str| evaluate_source(source).unwrap().to_string();
+    let classify = |value: &str| {
+        evaluate(&format!(
+            "var result = nil; \
+             match ({}) {{ \
+                 case 0 => result = \"zero\"; \
+                 case \"a\", \"b\" => result = \"letter\"; \
+                 case 1..10 => result = \"small\"; \
+                 case n if n < 0 => result = \"negative \" + str(n); \
+                 case _ => result = \"other\"; \
+             }} result;",
+            value
+        ))
+    };
+
+    assert_eq!(classify("0"), "zero");
+    assert_eq!(classify("\"b\""), "letter");
+    assert_eq!(classify("9"), "small");
+    assert_eq!(classify("10"), "other");
+    assert_eq!(classify("-3"), "negative -3");
+
+    // The first arm that matches wins, even when later ones would too.
+    assert_eq!(
+        evaluate("var r = nil; match (5) { case 1..=5 => r = 1; case 5 => r = 2; } r;"),
+        "1"
+    );
+
+    // The binding lives in the arm's own environment, it doesn't leak out or overwrite.
+    assert_eq!(
+        evaluate("var n = \"outer\"; match (1) { case n => { var inner = n; } } n;"),
+        "outer"
+    );
+    assert!(evaluate_source("match (1) { case n => nil; } n;").is_err());
+
+    // Without a hit nothing runs.
+    assert_eq!(
+        evaluate("var r = \"untouched\"; match (42) { case 1 => r = \"one\"; } r;"),
+        "untouched"
+    );
+}
+
+#[test]
+fn test_default_rest_and_keyword_arguments() {
+    let evaluate = |source: &str| evaluate_source(source).unwrap().to_string();
+    let message = |source: &str| match evaluate_source(source) {
+        Err(LoxError::Runtime { message, .. }) => message,
+        other => panic!("Expected a runtime error, got {:?}", other),
+    };
+
+    // Defaults are evaluated on every call and see the parameters before them.
+    assert_eq!(
+        evaluate("fun f(a, b = a * 2) { return [a, b]; } [f(1), f(2, 5)];"),
+        "[[1, 2], [2, 5]]"
+    );
+    assert_eq!(
+        evaluate("var n = 0; fun next(x = n) { n += 1; return x; } [next(), next(), next(10)];"),
+        "[0, 1, 10]"
+    );
+
+    assert_eq!(
+        evaluate("fun f(a, ...rest) { return [a, rest]; } [f(1), f(1, 2, 3)];"),
+        "[[1, []], [1, [2, 3]]]"
+    );
+
+    assert_eq!(
+        evaluate("fun f(a, b = 2, c = 3) { return [a, b, c]; } f(1, c: 30);"),
+        "[1, 2, 30]"
+    );
+    assert_eq!(
+        evaluate("fun f(a, b) { return a - b; } f(b: 1, a: 10);"),
+        "9"
+    );
+
+    // A repeated keyword is already caught by the parser.
+    assert!(matches!(
+        evaluate_source("fun f(a) {} f(a: 1, a: 2);"),
+        Err(LoxError::Parse)
+    ));
+    assert_eq!(
+        message("fun f(a) {} f(b: 1);"),
+        "'f' has no parameter named 'b'."
+    );
+    assert_eq!(
+        message("fun f(a, b) {} f(1, a: 2);"),
+        "Got more than one value for parameter 'a'."
+    );
+}
+
+#[test]
+fn test_destructuring_at_runtime() {
+    let evaluate = |source: &str| evaluate_source(source).unwrap().to_string();
+    let message = |source: &str| match evaluate_source(source) {
+        Err(LoxError::Runtime { message, .. }) => message,
+        other => panic!("Expected a runtime error, got {:?}", other),
+    };
+
+    assert_eq!(
+        evaluate("var [a, [b], ...rest] = [1, [2], 3, 4]; [a, b, rest];"),
+        "[1, 2, [3, 4]]"
+    );
+    assert_eq!(
+        evaluate("var {x, y: [first]} = {x: 1, y: [2]}; [x, first];"),
+        "[1, 2]"
+    );
+
+    // Both values are read before either name is assigned.
+    assert_eq!(
+        evaluate("var a = 1; var b = 2; [a, b] = [b, a]; [a, b];"),
+        "[2, 1]"
+    );
+    assert_eq!(
+        evaluate("var x = 0; var y = 0; {x, y} = {x: 3, y: 4}; x * 10 + y;"),
+        "34"
+    );
+
+    assert_eq!(
+        evaluate("fun f([a, b], {c}) { return a + b + c; } f([1, 2], {c: 3});"),
+        "6"
+    );
+
+    assert_eq!(
+        message("var [a, b] = [1]; nil;"),
+        "Expected 2 elements to destructure, but got 1."
+    );
+    assert_eq!(
+        message("var [a, b, ...rest] = [1]; nil;"),
+        "Expected at least 2 elements to destructure, but got 1."
+    );
+    assert_eq!(message("var {x} = {y: 1}; nil;"), "Map has no key 'x'.");
+    assert_eq!(
+        message("var [a] = 1; nil;"),
+        "Can't destructure 1 as a list."
+    );
+    assert_eq!(
+        message("fun f({a}) {} f([1]); nil;"),
+        "Can't destructure [1] as a map."
+    );
+}
@@ -0,0 +1,1284 @@
+use core::fmt;
+
+use super::{
+    ast_tools::{
+        Argument, BindingPattern, Expr, MatchArm, Parameter, Pattern, Stmt, TypeAnnotation,
+    },
+    error::LoxError,
+    token::{Literal, Token, TokenType},
+    typechecker::Type,
+};
+
+// A JSON document. Objects keep their keys in the order they were written so the output is
+// stable and easy to diff.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Json {
+    Null,
+    Bool(bool),
+    // NOTE: Numbers without a fraction or exponent stay integers so that `1` and `1.0` in a script
+    // don't turn into the same literal after a round-trip.
+    Integer(i64),
+    Float(f64),
+    String(String),
+    Array(Vec<Json>),
+    Object(Vec<(String, Json)>),
+}
+
+impl Json {
+    pub fn parse(source: &str) -> Result<Json, LoxError> {
+        let mut parser = JsonParser {
+            chars: source.chars().collect(),
+            current: 0,
+            depth: 0,
+        };
+        let json = parser.value()?;
+        parser.skip_whitespace();
+        if !parser.is_at_end() {
+            return Err(parser.error("Unexpected text after the JSON value."));
+        }
+        Ok(json)
+    }
+
+    // Spread over several lines with nested values indented by `indent` spaces. An indent of 0
+    // gives the same compact text as Display.
+    pub fn pretty(&self, indent: usize) -> String {
+        let mut printed = String::new();
+        if indent == 0 {
+            printed = self.to_string();
+        } else {
+            self.write_pretty(&mut printed, indent, 0);
+        }
+        printed
+    }
+
+    fn write_pretty(&self, printed: &mut String, indent: usize, depth: usize) {
+        let (open, close, count) = match self {
+            Json::Array(elements) => ('[', ']', elements.len()),
+            Json::Object(fields) => ('{', '}', fields.len()),
+            other => {
+                printed.push_str(&other.to_string());
+                return;
+            }
+        };
+        if count == 0 {
+            printed.push(open);
+            printed.push(close);
+            return;
+        }
+
+        let padding = " ".repeat(indent * (depth + 1));
+        printed.push(open);
+        for index in 0..count {
+            if index > 0 {
+                printed.push(',');
+            }
+            printed.push('\n');
+            printed.push_str(&padding);
+            let value = match self {
+                Json::Array(elements) => &elements[index],
+                Json::Object(fields) => {
+                    let (key, value) = &fields[index];
+                    printed.push_str(&Json::String(key.clone()).to_string());
+                    printed.push_str(": ");
+                    value
+                }
+                _ => unreachable!(),
+            };
+            value.write_pretty(printed, indent, depth + 1);
+        }
+        printed.push('\n');
+        printed.push_str(&" ".repeat(indent * depth));
+        printed.push(close);
+    }
+
+    // The value of a key in an object, None for missing keys and anything that isn't an object.
+    pub fn get(&self, key: &str) -> Option<&Json> {
+        match self {
+            Json::Object(fields) => fields
+                .iter()
+                .find(|(name, _)| name == key)
+                .map(|(_, value)| value),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for Json {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Json::Null => write!(f, "null"),
+            Json::Bool(bool) => write!(f, "{}", bool),
+            Json::Integer(integer) => write!(f, "{}", integer),
+            // NOTE: JSON has no NaN or infinity, null is what everyone else writes for them.
+            Json::Float(float) if !float.is_finite() => write!(f, "null"),
+            // Debug always keeps a `.0` or an exponent, Display would print `1.0` as `1`.
+            Json::Float(float) => write!(f, "{:?}", float),
+            Json::String(string) => write_string(f, string),
+            Json::Array(elements) => {
+                write!(f, "[")?;
+                for (i, element) in elements.iter().enumerate() {
+                    if i > 0 {
+                        write!(f, ",")?;
+                    }
+                    write!(f, "{}", element)?;
+                }
+                write!(f, "]")
+            }
+            Json::Object(fields) => {
+                write!(f, "{{")?;
+                for (i, (key, value)) in fields.iter().enumerate() {
+                    if i > 0 {
+                        write!(f, ",")?;
+                    }
+                    write_string(f, key)?;
+                    write!(f, ":{}", value)?;
+                }
+                write!(f, "}}")
+            }
+        }
+    }
+}
+
+fn write_string(f: &mut fmt::Formatter<'_>, string: &str) -> fmt::Result {
+    write!(f, "\"")?;
+    for c in string.chars() {
+        match c {
+            '"' => write!(f, "\\\"")?,
+            '\\' => write!(f, "\\\\")?,
+            '\n' => write!(f, "\\n")?,
+            '\r' => write!(f, "\\r")?,
+            '\t' => write!(f, "\\t")?,
+            c if (c as u32) < 0x20 => write!(f, "\\u{:04x}", c as u32)?,
+            c => write!(f, "{}", c)?,
+        }
+    }
+    write!(f, "\"")
+}
+
+// Arrays and objects are parsed recursively, past this many levels we'd run out of stack.
+const MAX_NESTING: usize = 512;
+
+struct JsonParser {
+    chars: Vec<char>,
+    current: usize,
+    // How many arrays and objects we're inside of right now.
+    depth: usize,
+}
+
+impl JsonParser {
+    fn value(&mut self) -> Result<Json, LoxError> {
+        self.skip_whitespace();
+        match self.peek() {
+            Some(open @ ('{' | '[')) => {
+                if self.depth == MAX_NESTING {
+                    return Err(self.error("Nesting is too deep."));
+                }
+                self.depth += 1;
+                let nested = if open == '{' {
+                    self.object()
+                } else {
+                    self.array()
+                };
+                self.depth -= 1;
+                nested
+            }
+            Some('"') => Ok(Json::String(self.string()?)),
+            Some('-' | '0'..='9') => self.number(),
+            Some(_) if self.match_word("true") => Ok(Json::Bool(true)),
+            Some(_) if self.match_word("false") => Ok(Json::Bool(false)),
+            Some(_) if self.match_word("null") => Ok(Json::Null),
+            Some(_) => Err(self.error("Expected a JSON value.")),
+            None => Err(self.error("Unexpected end of JSON.")),
+        }
+    }
+
+    fn object(&mut self) -> Result<Json, LoxError> {
+        self.current += 1;
+        let mut fields = Vec::new();
+
+        self.skip_whitespace();
+        if self.match_char('}') {
+            return Ok(Json::Object(fields));
+        }
+        loop {
+            self.skip_whitespace();
+            if self.peek() != Some('"') {
+                return Err(self.error("Expected a string key."));
+            }
+            let key = self.string()?;
+            self.skip_whitespace();
+            if !self.match_char(':') {
+                return Err(self.error("Expected ':' after key."));
+            }
+            fields.push((key, self.value()?));
+
+            self.skip_whitespace();
+            if self.match_char('}') {
+                return Ok(Json::Object(fields));
+            }
+            if !self.match_char(',') {
+                return Err(self.error("Expected ',' or '}' after object value."));
+            }
+        }
+    }
+
+    fn array(&mut self) -> Result<Json, LoxError> {
+        self.current += 1;
+        let mut elements = Vec::new();
+
+        self.skip_whitespace();
+        if self.match_char(']') {
+            return Ok(Json::Array(elements));
+        }
+        loop {
+            elements.push(self.value()?);
+
+            self.skip_whitespace();
+            if self.match_char(']') {
+                return Ok(Json::Array(elements));
+            }
+            if !self.match_char(',') {
+                return Err(self.error("Expected ',' or ']' after array element."));
+            }
+        }
+    }
+
+    fn string(&mut self) -> Result<String, LoxError> {
+        self.current += 1;
+        let mut string = String::new();
+
+        loop {
+            let Some(c) = self.advance() else {
+                return Err(self.error("Unterminated string."));
+            };
+            match c {
+                '"' => return Ok(string),
+                '\\' => {
+                    let escaped = match self.advance() {
+                        Some('"') => '"',
+                        Some('\\') => '\\',
+                        Some('/') => '/',
+                        Some('b') => '\u{8}',
+                        Some('f') => '\u{c}',
+                        Some('n') => '\n',
+                        Some('r') => '\r',
+                        Some('t') => '\t',
+                        Some('u') => self.unicode_escape()?,
+                        _ => return Err(self.error("Invalid escape sequence.")),
+                    };
+                    string.push(escaped);
+                }
+                c if (c as u32) < 0x20 => {
+                    return Err(self.error("Control characters must be escaped in strings."))
+                }
+                c => string.push(c),
+            }
+        }
+    }
+
+    // The part after `\u`. Characters outside the basic plane come as a pair of surrogates.
+    fn unicode_escape(&mut self) -> Result<char, LoxError> {
+        let high = self.hex_digits()?;
+        if !(0xD800..0xDC00).contains(&high) {
+            return char::from_u32(high).ok_or_else(|| self.error("Invalid unicode escape."));
+        }
+
+        if !(self.match_char('\\') && self.match_char('u')) {
+            return Err(self.error("Expected a low surrogate after a high surrogate."));
+        }
+        let low = self.hex_digits()?;
+        if !(0xDC00..0xE000).contains(&low) {
+            return Err(self.error("Invalid low surrogate."));
+        }
+        char::from_u32(0x10000 + ((high - 0xD800) << 10) + (low - 0xDC00))
+            .ok_or_else(|| self.error("Invalid unicode escape."))
+    }
+
+    fn hex_digits(&mut self) -> Result<u32, LoxError> {
+        let mut value = 0;
+        for _ in 0..4 {
+            let digit = self
+                .advance()
+                .and_then(|c| c.to_digit(16))
+                .ok_or_else(|| self.error("Expected four hex digits in unicode escape."))?;
+            value = value * 16 + digit;
+        }
+        Ok(value)
+    }
+
+    fn number(&mut self) -> Result<Json, LoxError> {
+        let start = self.current;
+        self.match_char('-');
+        if !self.match_char('0') && !self.match_digits() {
+            return Err(self.error("Expected a digit."));
+        }
+
+        let mut is_float = false;
+        if self.match_char('.') {
+            is_float = true;
+            if !self.match_digits() {
+                return Err(self.error("Expected a digit after the decimal point."));
+            }
+        }
+        if self.match_char('e') || self.match_char('E') {
+            is_float = true;
+            if !self.match_char('+') {
+                self.match_char('-');
+            }
+            if !self.match_digits() {
+                return Err(self.error("Expected a digit in the exponent."));
+            }
+        }
+
+        let text: String = self.chars[start..self.current].iter().collect();
+        if !is_float {
+            // NOTE: Integers too big for an i64 still parse, they just lose precision as floats.
+            if let Ok(integer) = text.parse::<i64>() {
+                return Ok(Json::Integer(integer));
+            }
+        }
+        text.parse::<f64>()
+            .map(Json::Float)
+            .map_err(|_| self.error("Invalid number."))
+    }
+
+    fn match_digits(&mut self) -> bool {
+        let start = self.current;
+        while self.peek().is_some_and(|c| c.is_ascii_digit()) {
+            self.current += 1;
+        }
+        self.current > start
+    }
+
+    fn match_word(&mut self, word: &str) -> bool {
+        let end = self.current + word.len();
+        if end <= self.chars.len()
+            && self.chars[self.current..end]
+                .iter()
+                .copied()
+                .eq(word.chars())
+        {
+            self.current = end;
+            return true;
+        }
+        false
+    }
+
+    fn match_char(&mut self, expected: char) -> bool {
+        if self.peek() == Some(expected) {
+            self.current += 1;
+            return true;
+        }
+        false
+    }
+
+    fn skip_whitespace(&mut self) {
+        while matches!(self.peek(), Some(' ' | '\t' | '\n' | '\r')) {
+            self.current += 1;
+        }
+    }
+
+    fn advance(&mut self) -> Option<char> {
+        let c = self.peek()?;
+        self.current += 1;
+        Some(c)
+    }
+
+    fn peek(&self) -> Option<char> {
+        self.chars.get(self.current).copied()
+    }
+
+    fn is_at_end(&self) -> bool {
+        self.current >= self.chars.len()
+    }
+
+    fn error(&self, message: &str) -> LoxError {
+        LoxError::Error(format!(
+            "Invalid JSON at character {}: {}",
+            self.current, message
+        ))
+    }
+}
+
+// Everything below turns tokens and syntax trees into JSON and back. Every node is an object with
+// a "kind" and one key per field of the node. Positions come from the tokens a node holds, each
+// token has its own line and column.
+
+pub fn tokens_to_json(tokens: &[Token]) -> Json {
+    Json::Array(tokens.iter().map(token_to_json).collect())
+}
+
+pub fn tokens_from_json(json: &Json) -> Result<Vec<Token>, LoxError> {
+    as_array(json)?.iter().map(token_from_json).collect()
+}
+
+pub fn statements_to_json(statements: &[Stmt]) -> Json {
+    Json::Array(statements.iter().map(stmt_to_json).collect())
+}
+
+pub fn statements_from_json(json: &Json) -> Result<Vec<Stmt>, LoxError> {
+    as_array(json)?.iter().map(stmt_from_json).collect()
+}
+
+fn node(kind: &str, fields: Vec<(&str, Json)>) -> Json {
+    let mut object = vec![("kind".to_string(), Json::String(kind.to_string()))];
+    object.extend(
+        fields
+            .into_iter()
+            .map(|(key, value)| (key.to_string(), value)),
+    );
+    Json::Object(object)
+}
+
+fn optional<T>(value: &Option<T>, to_json: impl Fn(&T) -> Json) -> Json {
+    value.as_ref().map_or(Json::Null, to_json)
+}
+
+fn boxed(expression: &Expr) -> Json {
+    expr_to_json(expression)
+}
+
+fn token_to_json(token: &Token) -> Json {
+    Json::Object(vec![
+        (
+            "type".to_string(),
+            Json::String(format!("{:?}", token.token_type)),
+        ),
+        ("lexeme".to_string(), Json::String(token.lexeme.clone())),
+        ("literal".to_string(), literal_to_json(&token.literal)),
+        ("line".to_string(), Json::Integer(token.line as i64)),
+        ("column".to_string(), Json::Integer(token.column as i64)),
+    ])
+}
+
+fn literal_to_json(literal: &Literal) -> Json {
+    match literal {
+        Literal::None => Json::Null,
+        Literal::Boolean(bool) => Json::Bool(*bool),
+        Literal::Integer(integer) => Json::Integer(*integer),
+        // NOTE: JSON has no way to write these, and a plain null would come back as nil.
+        Literal::Float(float) if !float.is_finite() => {
+            let name = if float.is_nan() {
+                "nan"
+            } else if *float > 0.0 {
+                "inf"
+            } else {
+                "-inf"
+            };
+            Json::Object(vec![("float".to_string(), Json::String(name.to_string()))])
+        }
+        Literal::Float(float) => Json::Float(*float),
+        Literal::String(string) => Json::String(string.clone()),
+    }
+}
+
+fn expr_to_json(expression: &Expr) -> Json {
+    match expression {
+        Expr::Binary {
+            left,
+            operator,
+            right,
+        } => node(
+            "Binary",
+            vec![
+                ("left", boxed(left)),
+                ("operator", token_to_json(operator)),
+                ("right", boxed(right)),
+            ],
+        ),
+        Expr::Logical {
+            left,
+            operator,
+            right,
+        } => node(
+            "Logical",
+            vec![
+                ("left", boxed(left)),
+                ("operator", token_to_json(operator)),
+                ("right", boxed(right)),
+            ],
+        ),
+        Expr::Grouping { expression } => node("Grouping", vec![("expression", boxed(expression))]),
+        Expr::Literal { value } => node("Literal", vec![("value", literal_to_json(value))]),
+        Expr::Unary { operator, right } => node(
+            "Unary",
+            vec![
+                ("operator", token_to_json(operator)),
+                ("right", boxed(right)),
+            ],
+        ),
+        Expr::Assign { name, value } => node(
+            "Assign",
+            vec![("name", token_to_json(name)), ("value", boxed(value))],
+        ),
+        Expr::Variable { name } => node("Variable", vec![("name", token_to_json(name))]),
+        Expr::Call {
+            callee,
+            paren,
+            arguments,
+        } => node(
+            "Call",
+            vec![
+                ("callee", boxed(callee)),
+                ("paren", token_to_json(paren)),
+                (
+                    "arguments",
+                    Json::Array(arguments.iter().map(argument_to_json).collect()),
+                ),
+            ],
+        ),
+        Expr::CompoundAssign {
+            target,
+            operator,
+            value,
+        } => node(
+            "CompoundAssign",
+            vec![
+                ("target", boxed(target)),
+                ("operator", token_to_json(operator)),
+                ("value", boxed(value)),
+            ],
+        ),
+        Expr::Increment {
+            target,
+            operator,
+            prefix,
+        } => node(
+            "Increment",
+            vec![
+                ("target", boxed(target)),
+                ("operator", token_to_json(operator)),
+                ("prefix", Json::Bool(*prefix)),
+            ],
+        ),
+        Expr::Conditional {
+            condition,
+            then_branch,
+            else_branch,
+        } => node(
+            "Conditional",
+            vec![
+                ("condition", boxed(condition)),
+                ("then_branch", boxed(then_branch)),
+                ("else_branch", boxed(else_branch)),
+            ],
+        ),
+        Expr::Get {
+            object,
+            name,
+            optional,
+        } => node(
+            "Get",
+            vec![
+                ("object", boxed(object)),
+                ("name", token_to_json(name)),
+                ("optional", Json::Bool(*optional)),
+            ],
+        ),
+        Expr::List { elements } => node(
+            "List",
+            vec![(
+                "elements",
+                Json::Array(elements.iter().map(expr_to_json).collect()),
+            )],
+        ),
+        Expr::Index {
+            object,
+            bracket,
+            index,
+        } => node(
+            "Index",
+            vec![
+                ("object", boxed(object)),
+                ("bracket", token_to_json(bracket)),
+                ("index", boxed(index)),
+            ],
+        ),
+        Expr::SetIndex {
+            object,
+            bracket,
+            index,
+            value,
+        } => node(
+            "SetIndex",
+            vec![
+                ("object", boxed(object)),
+                ("bracket", token_to_json(bracket)),
+                ("index", boxed(index)),
+                ("value", boxed(value)),
+            ],
+        ),
+        Expr::Map { brace, entries } => node(
+            "Map",
+            vec![
+                ("brace", token_to_json(brace)),
+                (
+                    "entries",
+                    Json::Array(
+                        entries
+                            .iter()
+                            .map(|(key, value)| {
+                                Json::Object(vec![
+                                    ("key".to_string(), token_to_json(key)),
+                                    ("value".to_string(), expr_to_json(value)),
+                                ])
+                            })
+                            .collect(),
+                    ),
+                ),
+            ],
+        ),
+        Expr::DestructureAssign {
+            pattern,
+            equals,
+            value,
+        } => node(
+            "DestructureAssign",
+            vec![
+                ("pattern", binding_to_json(pattern)),
+                ("equals", token_to_json(equals)),
+                ("value", boxed(value)),
+            ],
+        ),
+    }
+}
+
+fn argument_to_json(argument: &Argument) -> Json {
+    Json::Object(vec![
+        ("name".to_string(), optional(&argument.name, token_to_json)),
+        ("value".to_string(), expr_to_json(&argument.value)),
+    ])
+}
+
+fn binding_to_json(pattern: &BindingPattern) -> Json {
+    match pattern {
+        BindingPattern::Name(name) => node("Name", vec![("name", token_to_json(name))]),
+        BindingPattern::List {
+            bracket,
+            elements,
+            rest,
+        } => node(
+            "List",
+            vec![
+                ("bracket", token_to_json(bracket)),
+                (
+                    "elements",
+                    Json::Array(elements.iter().map(binding_to_json).collect()),
+                ),
+                ("rest", optional(rest, token_to_json)),
+            ],
+        ),
+        BindingPattern::Map { brace, fields } => node(
+            "Map",
+            vec![
+                ("brace", token_to_json(brace)),
+                (
+                    "fields",
+                    Json::Array(
+                        fields
+                            .iter()
+                            .map(|(key, pattern)| {
+                                Json::Object(vec![
+                                    ("key".to_string(), token_to_json(key)),
+                                    ("pattern".to_string(), binding_to_json(pattern)),
+                                ])
+                            })
+                            .collect(),
+                    ),
+                ),
+            ],
+        ),
+    }
+}
+
+fn pattern_to_json(pattern: &Pattern) -> Json {
+    match pattern {
+        Pattern::Literal(value) => node("Literal", vec![("value", literal_to_json(value))]),
+        Pattern::Range {
+            start,
+            end,
+            inclusive,
+        } => node(
+            "Range",
+            vec![
+                ("start", literal_to_json(start)),
+                ("end", literal_to_json(end)),
+                ("inclusive", Json::Bool(*inclusive)),
+            ],
+        ),
+        Pattern::Binding(name) => node("Binding", vec![("name", token_to_json(name))]),
+        Pattern::Wildcard => node("Wildcard", vec![]),
+    }
+}
+
+fn annotation_to_json(annotation: &TypeAnnotation) -> Json {
+    Json::Object(vec![
+        ("name".to_string(), token_to_json(&annotation.name)),
+        ("type".to_string(), Json::String(annotation.ty.to_string())),
+    ])
+}
+
+fn stmt_to_json(statement: &Stmt) -> Json {
+    match statement {
+        Stmt::Break { keyword, label } => node(
+            "Break",
+            vec![
+                ("keyword", token_to_json(keyword)),
+                ("label", optional(label, token_to_json)),
+            ],
+        ),
+        Stmt::Continue { keyword, label } => node(
+            "Continue",
+            vec![
+                ("keyword", token_to_json(keyword)),
+                ("label", optional(label, token_to_json)),
+            ],
+        ),
+        Stmt::Block { statements } => node(
+            "Block",
+            vec![("statements", statements_to_json(statements))],
+        ),
+        Stmt::While {
+            condition,
+            body,
+            increment,
+            label,
+        } => node(
+            "While",
+            vec![
+                ("condition", expr_to_json(condition)),
+                ("body", stmt_to_json(body)),
+                ("increment", optional(increment, expr_to_json)),
+                ("label", optional(label, token_to_json)),
+            ],
+        ),
+        Stmt::ForIn {
+            variable,
+            iterable,
+            body,
+            label,
+        } => node(
+            "ForIn",
+            vec![
+                ("variable", token_to_json(variable)),
+                ("iterable", expr_to_json(iterable)),
+                ("body", stmt_to_json(body)),
+                ("label", optional(label, token_to_json)),
+            ],
+        ),
+        Stmt::DoWhile {
+            body,
+            condition,
+            label,
+        } => node(
+            "DoWhile",
+            vec![
+                ("body", stmt_to_json(body)),
+                ("condition", expr_to_json(condition)),
+                ("label", optional(label, token_to_json)),
+            ],
+        ),
+        Stmt::Loop { body, label } => node(
+            "Loop",
+            vec![
+                ("body", stmt_to_json(body)),
+                ("label", optional(label, token_to_json)),
+            ],
+        ),
+        Stmt::Expression { expression } => {
+            node("Expression", vec![("expression", expr_to_json(expression))])
+        }
+        Stmt::Print { expression } => node("Print", vec![("expression", expr_to_json(expression))]),
+        Stmt::Var {
+            target,
+            annotation,
+            initializer,
+        } => node(
+            "Var",
+            vec![
+                ("target", binding_to_json(target)),
+                ("annotation", optional(annotation, annotation_to_json)),
+                ("initializer", optional(initializer, expr_to_json)),
+            ],
+        ),
+        Stmt::Const {
+            name,
+            annotation,
+            initializer,
+        } => node(
+            "Const",
+            vec![
+                ("name", token_to_json(name)),
+                ("annotation", optional(annotation, annotation_to_json)),
+                ("initializer", expr_to_json(initializer)),
+            ],
+        ),
+        Stmt::If {
+            condition,
+            then_branch,
+            else_branch,
+        } => node(
+            "If",
+            vec![
+                ("condition", expr_to_json(condition)),
+                ("then_branch", stmt_to_json(then_branch)),
+                ("else_branch", optional(else_branch, stmt_to_json)),
+            ],
+        ),
+        Stmt::Match {
+            keyword,
+            subject,
+            arms,
+        } => node(
+            "Match",
+            vec![
+                ("keyword", token_to_json(keyword)),
+                ("subject", expr_to_json(subject)),
+                ("arms", Json::Array(arms.iter().map(arm_to_json).collect())),
+            ],
+        ),
+        Stmt::Function {
+            name,
+            paramaters,
+            return_type,
+            body,
+        } => node(
+            "Function",
+            vec![
+                ("name", token_to_json(name)),
+                (
+                    "parameters",
+                    Json::Array(paramaters.iter().map(parameter_to_json).collect()),
+                ),
+                ("return_type", optional(return_type, annotation_to_json)),
+                ("body", statements_to_json(body)),
+            ],
+        ),
+        Stmt::Return { keyword, value } => node(
+            "Return",
+            vec![
+                ("keyword", token_to_json(keyword)),
+                ("value", optional(value, expr_to_json)),
+            ],
+        ),
+        // NOTE: What the parser leaves where a statement failed to parse. It can't be run, so it's
+        // written as the empty block it stands in for rather than a node of its own.
+        Stmt::NONE => node("Block", vec![("statements", Json::Array(Vec::new()))]),
+    }
+}
+
+fn arm_to_json(arm: &MatchArm) -> Json {
+    Json::Object(vec![
+        (
+            "patterns".to_string(),
+            Json::Array(arm.patterns.iter().map(pattern_to_json).collect()),
+        ),
+        ("guard".to_string(), optional(&arm.guard, expr_to_json)),
+        ("body".to_string(), stmt_to_json(&arm.body)),
+    ])
+}
+
+fn parameter_to_json(parameter: &Parameter) -> Json {
+    Json::Object(vec![
+        ("target".to_string(), binding_to_json(&parameter.target)),
+        (
+            "annotation".to_string(),
+            optional(&parameter.annotation, annotation_to_json),
+        ),
+        (
+            "default".to_string(),
+            optional(&parameter.default, expr_to_json),
+        ),
+        ("is_rest".to_string(), Json::Bool(parameter.is_rest)),
+    ])
+}
+
+fn shape_error(message: String) -> LoxError {
+    LoxError::Error(format!("Invalid syntax tree JSON: {}", message))
+}
+
+fn field<'a>(json: &'a Json, key: &str) -> Result<&'a Json, LoxError> {
+    json.get(key)
+        .ok_or_else(|| shape_error(format!("Missing field '{}'.", key)))
+}
+
+// Missing fields and nulls are both None.
+fn optional_field<T>(
+    json: &Json,
+    key: &str,
+    from_json: impl Fn(&Json) -> Result<T, LoxError>,
+) -> Result<Option<T>, LoxError> {
+    match json.get(key) {
+        None | Some(Json::Null) => Ok(None),
+        Some(value) => from_json(value).map(Some),
+    }
+}
+
+fn as_array(json: &Json) -> Result<&Vec<Json>, LoxError> {
+    match json {
+        Json::Array(elements) => Ok(elements),
+        other => Err(shape_error(format!("Expected an array, got {}.", other))),
+    }
+}
+
+fn as_str(json: &Json) -> Result<&str, LoxError> {
+    match json {
+        Json::String(string) => Ok(string),
+        other => Err(shape_error(format!("Expected a string, got {}.", other))),
+    }
+}
+
+fn as_bool(json: &Json) -> Result<bool, LoxError> {
+    match json {
+        Json::Bool(bool) => Ok(*bool),
+        other => Err(shape_error(format!("Expected a boolean, got {}.", other))),
+    }
+}
+
+fn as_usize(json: &Json) -> Result<usize, LoxError> {
+    match json {
+        Json::Integer(integer) if *integer >= 0 => Ok(*integer as usize),
+        other => Err(shape_error(format!(
+            "Expected a non-negative integer, got {}.",
+            other
+        ))),
+    }
+}
+
+fn kind(json: &Json) -> Result<&str, LoxError> {
+    as_str(field(json, "kind")?)
+}
+
+fn token_field(json: &Json, key: &str) -> Result<Token, LoxError> {
+    token_from_json(field(json, key)?)
+}
+
+fn expr_field(json: &Json, key: &str) -> Result<Expr, LoxError> {
+    expr_from_json(field(json, key)?)
+}
+
+fn boxed_field(json: &Json, key: &str) -> Result<Box<Expr>, LoxError> {
+    expr_field(json, key).map(Box::new)
+}
+
+fn stmt_field(json: &Json, key: &str) -> Result<Stmt, LoxError> {
+    stmt_from_json(field(json, key)?)
+}
+
+fn list_field<T>(
+    json: &Json,
+    key: &str,
+    from_json: impl Fn(&Json) -> Result<T, LoxError>,
+) -> Result<Vec<T>, LoxError> {
+    as_array(field(json, key)?)?.iter().map(from_json).collect()
+}
+
+fn token_from_json(json: &Json) -> Result<Token, LoxError> {
+    let name = as_str(field(json, "type")?)?;
+    let token_type = TokenType::from_name(name)
+        .ok_or_else(|| shape_error(format!("Unknown token type '{}'.", name)))?;
+
+    Ok(Token::new(
+        token_type,
+        as_str(field(json, "lexeme")?)?.to_string(),
+        literal_from_json(field(json, "literal")?)?,
+        as_usize(field(json, "line")?)?,
+        as_usize(field(json, "column")?)?,
+    ))
+}
+
+fn literal_from_json(json: &Json) -> Result<Literal, LoxError> {
+    match json {
+        Json::Null => Ok(Literal::None),
+        Json::Bool(bool) => Ok(Literal::Boolean(*bool)),
+        Json::Integer(integer) => Ok(Literal::Integer(*integer)),
+        Json::Float(float) => Ok(Literal::Float(*float)),
+        Json::String(string) => Ok(Literal::String(string.clone())),
+        Json::Object(_) => match as_str(field(json, "float")?)? {
+            "nan" => Ok(Literal::Float(f64::NAN)),
+            "inf" => Ok(Literal::Float(f64::INFINITY)),
+            "-inf" => Ok(Literal::Float(f64::NEG_INFINITY)),
+            other => Err(shape_error(format!("Expected a float, got '{}'.", other))),
+        },
+        other => Err(shape_error(format!("Expected a literal, got {}.", other))),
+    }
+}
+
+fn expr_from_json(json: &Json) -> Result<Expr, LoxError> {
+    Ok(match kind(json)? {
+        "Binary" => Expr::Binary {
+            left: boxed_field(json, "left")?,
+            operator: token_field(json, "operator")?,
+            right: boxed_field(json, "right")?,
+        },
+        "Logical" => Expr::Logical {
+            left: boxed_field(json, "left")?,
+            operator: token_field(json, "operator")?,
+            right: boxed_field(json, "right")?,
+        },
+        "Grouping" => Expr::Grouping {
+            expression: boxed_field(json, "expression")?,
+        },
+        "Literal" => Expr::Literal {
+            value: literal_from_json(field(json, "value")?)?,
+        },
+        "Unary" => Expr::Unary {
+            operator: token_field(json, "operator")?,
+            right: boxed_field(json, "right")?,
+        },
+        "Assign" => Expr::Assign {
+            name: token_field(json, "name")?,
+            value: boxed_field(json, "value")?,
+        },
+        "Variable" => Expr::Variable {
+            name: token_field(json, "name")?,
+        },
+        "Call" => Expr::Call {
+            callee: boxed_field(json, "callee")?,
+            paren: token_field(json, "paren")?,
+            arguments: list_field(json, "arguments", |argument| {
+                Ok(Argument {
+                    name: optional_field(argument, "name", token_from_json)?,
+                    value: expr_field(argument, "value")?,
+                })
+            })?,
+        },
+        "CompoundAssign" => Expr::CompoundAssign {
+            target: boxed_field(json, "target")?,
+            operator: token_field(json, "operator")?,
+            value: boxed_field(json, "value")?,
+        },
+        "Increment" => Expr::Increment {
+            target: boxed_field(json, "target")?,
+            operator: token_field(json, "operator")?,
+            prefix: as_bool(field(json, "prefix")?)?,
+        },
+        "Conditional" => Expr::Conditional {
+            condition: boxed_field(json, "condition")?,
+            then_branch: boxed_field(json, "then_branch")?,
+            else_branch: boxed_field(json, "else_branch")?,
+        },
+        "Get" => Expr::Get {
+            object: boxed_field(json, "object")?,
+            name: token_field(json, "name")?,
+            optional: as_bool(field(json, "optional")?)?,
+        },
+        "List" => Expr::List {
+            elements: list_field(json, "elements", expr_from_json)?,
+        },
+        "Index" => Expr::Index {
+            object: boxed_field(json, "object")?,
+            bracket: token_field(json, "bracket")?,
+            index: boxed_field(json, "index")?,
+        },
+        "SetIndex" => Expr::SetIndex {
+            object: boxed_field(json, "object")?,
+            bracket: token_field(json, "bracket")?,
+            index: boxed_field(json, "index")?,
+            value: boxed_field(json, "value")?,
+        },
+        "Map" => Expr::Map {
+            brace: token_field(json, "brace")?,
+            entries: list_field(json, "entries", |entry| {
+                Ok((token_field(entry, "key")?, expr_field(entry, "value")?))
+            })?,
+        },
+        "DestructureAssign" => Expr::DestructureAssign {
+            pattern: binding_from_json(field(json, "pattern")?)?,
+            equals: token_field(json, "equals")?,
+            value: boxed_field(json, "value")?,
+        },
+        other => return Err(shape_error(format!("Unknown expression kind '{}'.", other))),
+    })
+}
+
+fn binding_from_json(json: &Json) -> Result<BindingPattern, LoxError> {
+    Ok(match kind(json)? {
+        "Name" => BindingPattern::Name(token_field(json, "name")?),
+        "List" => BindingPattern::List {
+            bracket: token_field(json, "bracket")?,
+            elements: list_field(json, "elements", binding_from_json)?,
+            rest: optional_field(json, "rest", token_from_json)?,
+        },
+        "Map" => BindingPattern::Map {
+            brace: token_field(json, "brace")?,
+            fields: list_field(json, "fields", |entry| {
+                Ok((
+                    token_field(entry, "key")?,
+                    binding_from_json(field(entry, "pattern")?)?,
+                ))
+            })?,
+        },
+        other => return Err(shape_error(format!("Unknown binding kind '{}'.", other))),
+    })
+}
+
+fn pattern_from_json(json: &Json) -> Result<Pattern, LoxError> {
+    Ok(match kind(json)? {
+        "Literal" => Pattern::Literal(literal_from_json(field(json, "value")?)?),
+        "Range" => Pattern::Range {
+            start: literal_from_json(field(json, "start")?)?,
+            end: literal_from_json(field(json, "end")?)?,
+            inclusive: as_bool(field(json, "inclusive")?)?,
+        },
+        "Binding" => Pattern::Binding(token_field(json, "name")?),
+        "Wildcard" => Pattern::Wildcard,
+        other => return Err(shape_error(format!("Unknown pattern kind '{}'.", other))),
+    })
+}
+
+fn annotation_from_json(json: &Json) -> Result<TypeAnnotation, LoxError> {
+    let name = as_str(field(json, "type")?)?;
+    Ok(TypeAnnotation {
+        name: token_field(json, "name")?,
+        ty: Type::from_name(name)
+            .ok_or_else(|| shape_error(format!("Unknown type '{}'.", name)))?,
+    })
+}
+
+fn stmt_from_json(json: &Json) -> Result<Stmt, LoxError> {
+    let label = |json: &Json| optional_field(json, "label", token_from_json);
+
+    Ok(match kind(json)? {
+        "Break" => Stmt::Break {
+            keyword: token_field(json, "keyword")?,
+            label: label(json)?,
+        },
+        "Continue" => Stmt::Continue {
+            keyword: token_field(json, "keyword")?,
+            label: label(json)?,
+        },
+        "Block" => Stmt::Block {
+            statements: list_field(json, "statements", stmt_from_json)?,
+        },
+        "While" => Stmt::While {
+            condition: expr_field(json, "condition")?,
+            body: Box::new(stmt_field(json, "body")?),
+            increment: optional_field(json, "increment", expr_from_json)?,
+            label: label(json)?,
+        },
+        "ForIn" => Stmt::ForIn {
+            variable: token_field(json, "variable")?,
+            iterable: expr_field(json, "iterable")?,
+            body: Box::new(stmt_field(json, "body")?),
+            label: label(json)?,
+        },
+        "DoWhile" => Stmt::DoWhile {
+            body: Box::new(stmt_field(json, "body")?),
+            condition: expr_field(json, "condition")?,
+            label: label(json)?,
+        },
+        "Loop" => Stmt::Loop {
+            body: Box::new(stmt_field(json, "body")?),
+            label: label(json)?,
+        },
+        "Expression" => Stmt::Expression {
+            expression: expr_field(json, "expression")?,
+        },
+        "Print" => Stmt::Print {
+            expression: expr_field(json, "expression")?,
+        },
+        "Var" => Stmt::Var {
+            target: binding_from_json(field(json, "target")?)?,
+            annotation: optional_field(json, "annotation", annotation_from_json)?,
+            initializer: optional_field(json, "initializer", expr_from_json)?,
+        },
+        "Const" => Stmt::Const {
+            name: token_field(json, "name")?,
+            annotation: optional_field(json, "annotation", annotation_from_json)?,
+            initializer: expr_field(json, "initializer")?,
+        },
+        "If" => Stmt::If {
+            condition: expr_field(json, "condition")?,
+            then_branch: Box::new(stmt_field(json, "then_branch")?),
+            else_branch: Box::new(optional_field(json, "else_branch", stmt_from_json)?),
+        },
+        "Match" => Stmt::Match {
+            keyword: token_field(json, "keyword")?,
+            subject: expr_field(json, "subject")?,
+            arms: list_field(json, "arms", |arm| {
+                Ok(MatchArm {
+                    patterns: list_field(arm, "patterns", pattern_from_json)?,
+                    guard: optional_field(arm, "guard", expr_from_json)?,
+                    body: stmt_field(arm, "body")?,
+                })
+            })?,
+        },
+        "Function" => Stmt::Function {
+            name: token_field(json, "name")?,
+            paramaters: list_field(json, "parameters", |parameter| {
+                Ok(Parameter {
+                    target: binding_from_json(field(parameter, "target")?)?,
+                    annotation: optional_field(parameter, "annotation", annotation_from_json)?,
+                    default: optional_field(parameter, "default", expr_from_json)?,
+                    is_rest: as_bool(field(parameter, "is_rest")?)?,
+                })
+            })?,
+            return_type: optional_field(json, "return_type", annotation_from_json)?,
+            body: list_field(json, "body", stmt_from_json)?,
+        },
+        "Return" => Stmt::Return {
+            keyword: token_field(json, "keyword")?,
+            value: optional_field(json, "value", expr_from_json)?,
+        },
+        other => return Err(shape_error(format!("Unknown statement kind '{}'.", other))),
+    })
+}
+
+#[test]
+fn test_json_round_trip() {
+    use crate::lox_interpreter::{
+        ast_tools::ASTPrinter, interpreter::Interpreter, interpreter::Object, parser::Parser,
+        scanner::Scanner,
+    };
+
+    let source = "const limit: Number = 3;\n\
+                  fun sum(first, second = 10, ...others) {\n\
+                      var total = first + second;\n\
+                      for (n in others) total += n;\n\
+                      return total;\n\
+                  }\n\
+                  var [a, {b, c: [d]}] = [1, {b: \"two\\n\", c: [2.5]}];\n\
+                  var result = 0;\n\
+                  outer: while (result < limit) { result++; if (result == 2) continue outer; }\n\
+                  match (a) { case 0..=1, _ if true => result = sum(result, second: 1); }\n\
+                  var answer = result + d + sum(1, 2, 3) ?? nil;\n\
+                  var huge = 1e999;";
+    let tokens = Scanner::new(source.into()).scan_tokens().unwrap();
+
+    // Tokens keep their type, lexeme, literal and position.
+    let tokens_json = tokens_to_json(&tokens).to_string();
+    let read_back = tokens_from_json(&Json::parse(&tokens_json).unwrap()).unwrap();
+    assert_eq!(tokens_to_json(&read_back).to_string(), tokens_json);
+
+    let statements = Parser::new(tokens).parse().unwrap();
+    let json = statements_to_json(&statements).to_string();
+    let read_back = statements_from_json(&Json::parse(&json).unwrap()).unwrap();
+
+    assert_eq!(statements_to_json(&read_back).to_string(), json);
+    assert_eq!(
+        ASTPrinter::new().print_statements(&read_back).unwrap(),
+        ASTPrinter::new().print_statements(&statements).unwrap()
+    );
+
+    let mut interpreter = Interpreter::new();
+    interpreter.interpret(read_back).unwrap();
+    let answer = Scanner::new("answer".into()).scan_tokens().unwrap()[0].clone();
+    match interpreter.evaluate(&Expr::Variable { name: answer }) {
+        Ok(Object::Number(n)) => assert_eq!(n, 12.5),
+        other => panic!("Expected answer to be 12.5, got {:?}", other),
+    }
+    let huge = Scanner::new("huge".into()).scan_tokens().unwrap()[0].clone();
+    match interpreter.evaluate(&Expr::Variable { name: huge }) {
+        Ok(Object::Number(n)) => assert_eq!(n, f64::INFINITY),
+        other => panic!("Expected huge to be inf, got {:?}", other),
+    }
+
+    assert!(Json::parse("{\"kind\": }").is_err());
+    assert!(statements_from_json(&Json::parse("[{\"kind\": \"Nope\"}]").unwrap()).is_err());
+    assert!(statements_from_json(&Json::parse("[{\"kind\": \"None\"}]").unwrap()).is_err());
+    assert_eq!(
+        statements_to_json(&[Stmt::NONE]).to_string(),
+        r#"[{"kind":"Block","statements":[]}]"#
+    );
+}
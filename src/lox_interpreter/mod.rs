@@ -1,31 +1,109 @@
 // TODO: ADD FUNCTIONALITY OF BREAK FOR LOOPS.
-use std::{fs, io, io::Write};
-
-use error::LoxError;
-use interpreter::Interpreter;
+use std::{
+    cell::RefCell,
+    fs, io,
+    io::{Read, Write},
+    rc::Rc,
+    time::{Duration, Instant},
+};
+
+use ast_tools::{ASTPrinter, ASTTreePrinter};
+use error::{report_runtime_error, LoxError};
+use interpreter::{Interpreter, Object};
+use json::{statements_to_json, tokens_to_json, Json};
+use optimizer::Optimizer;
 use parser::Parser;
+use resolver::Resolver;
 use scanner::Scanner;
+use stdlib::random::Rng;
+use typechecker::TypeChecker;
 
 pub mod ast_tools;
 pub mod environment;
 pub mod error;
 pub mod function;
 pub mod interpreter;
+pub mod json;
+pub mod optimizer;
 pub mod parser;
+pub mod resolver;
 pub mod scanner;
+pub mod stdlib;
 pub mod token;
+pub mod typechecker;
+
+// How to print the parsed tree instead of running it.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum AstDump {
+    // One s-expression per statement.
+    Sexpr,
+    // One node per line, indented, with token positions.
+    Tree,
+    // The tokens and the tree as one JSON object, for other tools to read.
+    Json,
+}
 
 pub struct Lox {
     had_error: bool,
+    // Run the optimizer over the tree before interpreting it.
+    optimize: bool,
+    dump: Option<AstDump>,
+    // Run scripts without access to files or stdin.
+    sandboxed: bool,
+    // Fixed seed for the random natives, None seeds them from the clock.
+    seed: Option<u64>,
+    // How long a script gets to run before it's stopped.
+    timeout: Option<Duration>,
+    // Whatever came after the script on the command line, scripts see it as `args`.
+    script_args: Vec<String>,
 }
 
 impl Lox {
     pub fn new() -> Self {
-        Lox { had_error: false }
+        Lox {
+            had_error: false,
+            optimize: false,
+            dump: None,
+            sandboxed: false,
+            seed: None,
+            timeout: None,
+            script_args: Vec::new(),
+        }
+    }
+
+    pub fn set_optimize(&mut self, optimize: bool) {
+        self.optimize = optimize;
+    }
+
+    pub fn set_sandboxed(&mut self, sandboxed: bool) {
+        self.sandboxed = sandboxed;
+    }
+
+    pub fn set_seed(&mut self, seed: Option<u64>) {
+        self.seed = seed;
+    }
+
+    pub fn set_timeout(&mut self, timeout: Option<Duration>) {
+        self.timeout = timeout;
     }
 
+    pub fn set_script_args(&mut self, script_args: Vec<String>) {
+        self.script_args = script_args;
+    }
+
+    pub fn set_dump(&mut self, dump: Option<AstDump>) {
+        self.dump = dump;
+    }
+
+    // A file name of `-` reads the script from stdin instead.
     pub fn run_file(&mut self, file_name: String) -> Result<(), LoxError> {
-        let file_contents = fs::read(file_name)?;
+        let file_contents = if file_name == "-" {
+            let mut contents = Vec::new();
+            io::stdin().read_to_end(&mut contents)?;
+            contents
+        } else {
+            fs::read(file_name)?
+        };
         self.run(file_contents)?;
 
         if self.had_error {
@@ -62,10 +140,46 @@ impl Lox {
         let tokens = scanner.scan_tokens()?;
         //println!("Tokens: {:#?}", tokens);
 
+        // NOTE: The parser takes the tokens, the JSON dump wants them as well.
+        let dumped_tokens = match self.dump {
+            Some(AstDump::Json) => tokens_to_json(&tokens),
+            _ => Json::Null,
+        };
+
         let mut parser = Parser::new(tokens);
         // TODO: I should not be escalating the error here, but for now it's fine.
         let statements = parser.parse()?;
 
+        // NOTE: Dumping happens before the later passes so a tree they'd reject can still be looked at.
+        if let Some(dump) = self.dump {
+            let statements = if self.optimize {
+                Optimizer::new().optimize(&statements)?
+            } else {
+                statements
+            };
+            let printed = match dump {
+                AstDump::Sexpr => ASTPrinter::new().print_statements(&statements)?,
+                AstDump::Tree => ASTTreePrinter::new().print_statements(&statements)?,
+                AstDump::Json => {
+                    let json = Json::Object(vec![
+                        ("tokens".to_string(), dumped_tokens),
+                        ("statements".to_string(), statements_to_json(&statements)),
+                    ]);
+                    format!("{}\n", json)
+                }
+            };
+            print!("{}", printed);
+            return Ok(());
+        }
+
+        Resolver::new().resolve(&statements)?;
+        TypeChecker::new().check(&statements)?;
+        let statements = if self.optimize {
+            Optimizer::new().optimize(&statements)?
+        } else {
+            statements
+        };
+
         if self.had_error {
             //println!("Got An Error.");
             return Err(LoxError::Error("Error running.".to_string()));
@@ -73,7 +187,29 @@ impl Lox {
 
         //println!("Statements: {:#?}", statements);
         let mut intpereter = Interpreter::new();
-        intpereter.interpret(statements)?;
+        intpereter.set_sandboxed(self.sandboxed);
+        let args = self
+            .script_args
+            .iter()
+            .cloned()
+            .map(Object::String)
+            .collect();
+        intpereter.define_global("args", Object::List(Rc::new(RefCell::new(args))))?;
+        intpereter.set_deadline(self.timeout.map(|timeout| Instant::now() + timeout));
+        if let Some(seed) = self.seed {
+            *intpereter.rng() = Rng::new(seed);
+        }
+        if let Err(error) = intpereter.interpret(statements) {
+            match &error {
+                LoxError::Runtime { token, message }
+                | LoxError::RuntimeIo { token, message, .. } => {
+                    report_runtime_error(token, message)
+                }
+                LoxError::Timeout => eprintln!("Runtime Error: Script ran past its time limit."),
+                _ => {}
+            }
+            return Err(error);
+        }
 
         Ok(())
     }
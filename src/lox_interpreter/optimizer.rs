@@ -0,0 +1,621 @@
+use super::{
+    ast_tools::{
+        expr, stmt, Argument, BindingPattern, Expr, MatchArm, Parameter, Stmt, TypeAnnotation,
+    },
+    error::LoxError,
+    interpreter::{Interpreter, Object},
+    token::{Literal, Token, TokenType},
+};
+
+// Rewrites the tree before it gets run. Expressions made up of nothing but literals are worked
+// out once here instead of every time they're evaluated, and code that can never run is dropped.
+pub struct Optimizer {
+    // NOTE: Folding goes through the interpreter, so a folded expression gives exactly what running
+    // it would have.
+    interpreter: Interpreter,
+}
+
+impl Default for Optimizer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Optimizer {
+    pub fn new() -> Self {
+        Optimizer {
+            interpreter: Interpreter::new(),
+        }
+    }
+
+    pub fn optimize(&mut self, statements: &[Stmt]) -> Result<Vec<Stmt>, LoxError> {
+        self.optimize_block(statements)
+    }
+
+    fn optimize_block(&mut self, statements: &[Stmt]) -> Result<Vec<Stmt>, LoxError> {
+        let mut optimized = Vec::with_capacity(statements.len());
+        for statement in statements {
+            let Some(statement) = statement.accept(self)? else {
+                continue;
+            };
+
+            // Nothing after a return, break or continue in the same block can ever run.
+            let jumps_away = matches!(
+                statement,
+                Stmt::Return { .. } | Stmt::Break { .. } | Stmt::Continue { .. }
+            );
+            optimized.push(statement);
+            if jumps_away {
+                break;
+            }
+        }
+        Ok(optimized)
+    }
+
+    // The body of a loop or an if has to stay a statement even if nothing is left of it.
+    fn optimize_body(&mut self, statement: &Stmt) -> Result<Stmt, LoxError> {
+        Ok(statement.accept(self)?.unwrap_or(Stmt::Block {
+            statements: Vec::new(),
+        }))
+    }
+
+    fn optimize_expr(&mut self, expression: &Expr) -> Result<Expr, LoxError> {
+        expression.accept(self)
+    }
+
+    fn boxed(&mut self, expression: &Expr) -> Result<Box<Expr>, LoxError> {
+        Ok(Box::new(self.optimize_expr(expression)?))
+    }
+
+    fn optional(&mut self, expression: &Option<Expr>) -> Result<Option<Expr>, LoxError> {
+        expression
+            .as_ref()
+            .map(|expression| self.optimize_expr(expression))
+            .transpose()
+    }
+
+    // Works out an expression whose operands are all literals. Anything that fails, like dividing
+    // by zero or overflowing, is left as it is so the error still happens if and when it runs.
+    fn fold(&mut self, expression: Expr) -> Expr {
+        let operands_are_literals = match &expression {
+            Expr::Binary { left, right, .. } | Expr::Logical { left, right, .. } => {
+                is_literal(left) && is_literal(right)
+            }
+            Expr::Unary { right, .. } => is_literal(right),
+            Expr::Grouping { expression } => is_literal(expression),
+            _ => false,
+        };
+        if !operands_are_literals {
+            return expression;
+        }
+
+        match self.interpreter.evaluate(&expression) {
+            Ok(value) => match literal(&value) {
+                Some(value) => Expr::Literal { value },
+                None => expression,
+            },
+            Err(_) => expression,
+        }
+    }
+}
+
+fn is_literal(expression: &Expr) -> bool {
+    matches!(expression, Expr::Literal { .. })
+}
+
+fn literal(value: &Object) -> Option<Literal> {
+    match value {
+        Object::Boolean(boolean) => Some(Literal::Boolean(*boolean)),
+        Object::NONE => Some(Literal::None),
+        Object::Number(number) => Some(Literal::Float(*number)),
+        Object::Integer(integer) => Some(Literal::Integer(*integer)),
+        Object::String(string) => Some(Literal::String(string.clone())),
+        _ => None,
+    }
+}
+
+// Same as the interpreter, only nil and false are false.
+fn is_truthy(value: &Literal) -> bool {
+    !matches!(value, Literal::None | Literal::Boolean(false))
+}
+
+impl expr::Visitor<Expr> for Optimizer {
+    fn visit_binary_expr(
+        &mut self,
+        left: &Expr,
+        operator: &Token,
+        right: &Expr,
+    ) -> Result<Expr, LoxError> {
+        let expression = Expr::Binary {
+            left: self.boxed(left)?,
+            operator: operator.clone(),
+            right: self.boxed(right)?,
+        };
+        Ok(self.fold(expression))
+    }
+
+    fn visit_grouping_expr(&mut self, expression: &Expr) -> Result<Expr, LoxError> {
+        let expression = Expr::Grouping {
+            expression: self.boxed(expression)?,
+        };
+        Ok(self.fold(expression))
+    }
+
+    fn visit_literal_expr(&mut self, value: &Literal) -> Result<Expr, LoxError> {
+        Ok(Expr::Literal {
+            value: value.clone(),
+        })
+    }
+
+    fn visit_unary_expr(&mut self, operator: &Token, right: &Expr) -> Result<Expr, LoxError> {
+        let expression = Expr::Unary {
+            operator: operator.clone(),
+            right: self.boxed(right)?,
+        };
+        Ok(self.fold(expression))
+    }
+
+    fn visit_variable_expr(&mut self, name: &Token) -> Result<Expr, LoxError> {
+        Ok(Expr::Variable { name: name.clone() })
+    }
+
+    fn visit_assignment_expr(&mut self, name: &Token, value: &Expr) -> Result<Expr, LoxError> {
+        Ok(Expr::Assign {
+            name: name.clone(),
+            value: self.boxed(value)?,
+        })
+    }
+
+    fn visit_logical_expr(
+        &mut self,
+        left: &Expr,
+        operator: &Token,
+        right: &Expr,
+    ) -> Result<Expr, LoxError> {
+        let left = self.optimize_expr(left)?;
+        let right = self.optimize_expr(right)?;
+
+        // A literal on the left decides right away whether the right side is needed at all.
+        if let Expr::Literal { value } = &left {
+            let takes_left = match operator.token_type {
+                TokenType::OR => is_truthy(value),
+                TokenType::QUESTION_QUESTION => !matches!(value, Literal::None),
+                _ => !is_truthy(value),
+            };
+            return Ok(if takes_left { left } else { right });
+        }
+
+        Ok(Expr::Logical {
+            left: Box::new(left),
+            operator: operator.clone(),
+            right: Box::new(right),
+        })
+    }
+
+    fn visit_call_expr(
+        &mut self,
+        callee: &Expr,
+        pren: &Token,
+        arguments: &Vec<Argument>,
+    ) -> Result<Expr, LoxError> {
+        let mut optimized = Vec::with_capacity(arguments.len());
+        for argument in arguments {
+            optimized.push(Argument {
+                name: argument.name.clone(),
+                value: self.optimize_expr(&argument.value)?,
+            });
+        }
+
+        Ok(Expr::Call {
+            callee: self.boxed(callee)?,
+            paren: pren.clone(),
+            arguments: optimized,
+        })
+    }
+
+    fn visit_compound_assign_expr(
+        &mut self,
+        target: &Expr,
+        operator: &Token,
+        value: &Expr,
+    ) -> Result<Expr, LoxError> {
+        Ok(Expr::CompoundAssign {
+            target: self.boxed(target)?,
+            operator: operator.clone(),
+            value: self.boxed(value)?,
+        })
+    }
+
+    fn visit_increment_expr(
+        &mut self,
+        target: &Expr,
+        operator: &Token,
+        prefix: bool,
+    ) -> Result<Expr, LoxError> {
+        Ok(Expr::Increment {
+            target: self.boxed(target)?,
+            operator: operator.clone(),
+            prefix,
+        })
+    }
+
+    fn visit_conditional_expr(
+        &mut self,
+        condition: &Expr,
+        then_branch: &Expr,
+        else_branch: &Expr,
+    ) -> Result<Expr, LoxError> {
+        let condition = self.optimize_expr(condition)?;
+        let then_branch = self.optimize_expr(then_branch)?;
+        let else_branch = self.optimize_expr(else_branch)?;
+
+        if let Expr::Literal { value } = &condition {
+            return Ok(if is_truthy(value) {
+                then_branch
+            } else {
+                else_branch
+            });
+        }
+
+        Ok(Expr::Conditional {
+            condition: Box::new(condition),
+            then_branch: Box::new(then_branch),
+            else_branch: Box::new(else_branch),
+        })
+    }
+
+    fn visit_get_expr(
+        &mut self,
+        object: &Expr,
+        name: &Token,
+        optional: bool,
+    ) -> Result<Expr, LoxError> {
+        Ok(Expr::Get {
+            object: self.boxed(object)?,
+            name: name.clone(),
+            optional,
+        })
+    }
+
+    fn visit_list_expr(&mut self, elements: &Vec<Expr>) -> Result<Expr, LoxError> {
+        let mut optimized = Vec::with_capacity(elements.len());
+        for element in elements {
+            optimized.push(self.optimize_expr(element)?);
+        }
+        Ok(Expr::List {
+            elements: optimized,
+        })
+    }
+
+    fn visit_index_expr(
+        &mut self,
+        object: &Expr,
+        bracket: &Token,
+        index: &Expr,
+    ) -> Result<Expr, LoxError> {
+        Ok(Expr::Index {
+            object: self.boxed(object)?,
+            bracket: bracket.clone(),
+            index: self.boxed(index)?,
+        })
+    }
+
+    fn visit_set_index_expr(
+        &mut self,
+        object: &Expr,
+        bracket: &Token,
+        index: &Expr,
+        value: &Expr,
+    ) -> Result<Expr, LoxError> {
+        Ok(Expr::SetIndex {
+            object: self.boxed(object)?,
+            bracket: bracket.clone(),
+            index: self.boxed(index)?,
+            value: self.boxed(value)?,
+        })
+    }
+
+    fn visit_map_expr(
+        &mut self,
+        brace: &Token,
+        entries: &Vec<(Token, Expr)>,
+    ) -> Result<Expr, LoxError> {
+        let mut optimized = Vec::with_capacity(entries.len());
+        for (key, value) in entries {
+            optimized.push((key.clone(), self.optimize_expr(value)?));
+        }
+        Ok(Expr::Map {
+            brace: brace.clone(),
+            entries: optimized,
+        })
+    }
+
+    fn visit_destructure_assign_expr(
+        &mut self,
+        pattern: &BindingPattern,
+        equals: &Token,
+        value: &Expr,
+    ) -> Result<Expr, LoxError> {
+        Ok(Expr::DestructureAssign {
+            pattern: pattern.clone(),
+            equals: equals.clone(),
+            value: self.boxed(value)?,
+        })
+    }
+}
+
+// None means the statement can go, it would never do anything.
+impl stmt::Visitor<Option<Stmt>> for Optimizer {
+    fn visit_block_stmt(&mut self, statements: &Vec<Stmt>) -> Result<Option<Stmt>, LoxError> {
+        let statements = self.optimize_block(statements)?;
+        if statements.is_empty() {
+            return Ok(None);
+        }
+        Ok(Some(Stmt::Block { statements }))
+    }
+
+    fn visit_expression_stmt(&mut self, expression: &Expr) -> Result<Option<Stmt>, LoxError> {
+        let expression = self.optimize_expr(expression)?;
+        if is_literal(&expression) {
+            return Ok(None);
+        }
+        Ok(Some(Stmt::Expression { expression }))
+    }
+
+    fn visit_print_stmt(&mut self, expression: &Expr) -> Result<Option<Stmt>, LoxError> {
+        Ok(Some(Stmt::Print {
+            expression: self.optimize_expr(expression)?,
+        }))
+    }
+
+    fn visit_var_stmt(
+        &mut self,
+        target: &BindingPattern,
+        annotation: &Option<TypeAnnotation>,
+        initializer: &Option<Expr>,
+    ) -> Result<Option<Stmt>, LoxError> {
+        Ok(Some(Stmt::Var {
+            target: target.clone(),
+            annotation: annotation.clone(),
+            initializer: self.optional(initializer)?,
+        }))
+    }
+
+    fn visit_const_stmt(
+        &mut self,
+        name: &Token,
+        annotation: &Option<TypeAnnotation>,
+        initializer: &Expr,
+    ) -> Result<Option<Stmt>, LoxError> {
+        Ok(Some(Stmt::Const {
+            name: name.clone(),
+            annotation: annotation.clone(),
+            initializer: self.optimize_expr(initializer)?,
+        }))
+    }
+
+    fn visit_if_statement(
+        &mut self,
+        condition: &Expr,
+        then_branch: &Stmt,
+        else_branch: &Option<Stmt>,
+    ) -> Result<Option<Stmt>, LoxError> {
+        let condition = self.optimize_expr(condition)?;
+
+        // Only the branch that's taken survives.
+        if let Expr::Literal { value } = &condition {
+            return match (is_truthy(value), else_branch) {
+                (true, _) => then_branch.accept(self),
+                (false, Some(else_branch)) => else_branch.accept(self),
+                (false, None) => Ok(None),
+            };
+        }
+
+        let else_branch = match else_branch {
+            Some(else_branch) => else_branch.accept(self)?,
+            None => None,
+        };
+        Ok(Some(Stmt::If {
+            condition,
+            then_branch: Box::new(self.optimize_body(then_branch)?),
+            else_branch: Box::new(else_branch),
+        }))
+    }
+
+    fn visit_while_statement(
+        &mut self,
+        condition: &Expr,
+        body: &Box<Stmt>,
+        increment: &Option<Expr>,
+        label: &Option<Token>,
+    ) -> Result<Option<Stmt>, LoxError> {
+        let condition = self.optimize_expr(condition)?;
+        if let Expr::Literal { value } = &condition {
+            if !is_truthy(value) {
+                return Ok(None);
+            }
+        }
+
+        Ok(Some(Stmt::While {
+            condition,
+            body: Box::new(self.optimize_body(body)?),
+            increment: self.optional(increment)?,
+            label: label.clone(),
+        }))
+    }
+
+    fn visit_for_in_stmt(
+        &mut self,
+        variable: &Token,
+        iterable: &Expr,
+        body: &Stmt,
+        label: &Option<Token>,
+    ) -> Result<Option<Stmt>, LoxError> {
+        Ok(Some(Stmt::ForIn {
+            variable: variable.clone(),
+            iterable: self.optimize_expr(iterable)?,
+            body: Box::new(self.optimize_body(body)?),
+            label: label.clone(),
+        }))
+    }
+
+    fn visit_do_while_stmt(
+        &mut self,
+        body: &Stmt,
+        condition: &Expr,
+        label: &Option<Token>,
+    ) -> Result<Option<Stmt>, LoxError> {
+        Ok(Some(Stmt::DoWhile {
+            body: Box::new(self.optimize_body(body)?),
+            condition: self.optimize_expr(condition)?,
+            label: label.clone(),
+        }))
+    }
+
+    fn visit_loop_stmt(
+        &mut self,
+        body: &Stmt,
+        label: &Option<Token>,
+    ) -> Result<Option<Stmt>, LoxError> {
+        Ok(Some(Stmt::Loop {
+            body: Box::new(self.optimize_body(body)?),
+            label: label.clone(),
+        }))
+    }
+
+    fn visit_match_stmt(
+        &mut self,
+        keyword: &Token,
+        subject: &Expr,
+        arms: &Vec<MatchArm>,
+    ) -> Result<Option<Stmt>, LoxError> {
+        let mut optimized = Vec::with_capacity(arms.len());
+        for arm in arms {
+            optimized.push(MatchArm {
+                patterns: arm.patterns.clone(),
+                guard: self.optional(&arm.guard)?,
+                body: self.optimize_body(&arm.body)?,
+            });
+        }
+
+        Ok(Some(Stmt::Match {
+            keyword: keyword.clone(),
+            subject: self.optimize_expr(subject)?,
+            arms: optimized,
+        }))
+    }
+
+    fn visit_break_stmt(
+        &mut self,
+        keyword: &Token,
+        label: &Option<Token>,
+    ) -> Result<Option<Stmt>, LoxError> {
+        Ok(Some(Stmt::Break {
+            keyword: keyword.clone(),
+            label: label.clone(),
+        }))
+    }
+
+    fn visit_continue_stmt(
+        &mut self,
+        keyword: &Token,
+        label: &Option<Token>,
+    ) -> Result<Option<Stmt>, LoxError> {
+        Ok(Some(Stmt::Continue {
+            keyword: keyword.clone(),
+            label: label.clone(),
+        }))
+    }
+
+    fn visit_function_stmt(
+        &mut self,
+        name: &Token,
+        paramaters: &Vec<Parameter>,
+        return_type: &Option<TypeAnnotation>,
+        body: &Vec<Stmt>,
+    ) -> Result<Option<Stmt>, LoxError> {
+        let mut optimized = Vec::with_capacity(paramaters.len());
+        for param in paramaters {
+            optimized.push(Parameter {
+                default: self.optional(&param.default)?,
+                ..param.clone()
+            });
+        }
+
+        Ok(Some(Stmt::Function {
+            name: name.clone(),
+            paramaters: optimized,
+            return_type: return_type.clone(),
+            body: self.optimize_block(body)?,
+        }))
+    }
+
+    fn visit_return_stmt(
+        &mut self,
+        keyword: &Token,
+        value: &Option<Expr>,
+    ) -> Result<Option<Stmt>, LoxError> {
+        Ok(Some(Stmt::Return {
+            keyword: keyword.clone(),
+            value: self.optional(value)?,
+        }))
+    }
+}
+
+// Every `.lox` file in test_files/optimizer has the dump of its optimized tree next to it in a
+// `.ast` file.
+#[test]
+fn test_optimizer_golden_files() {
+    use crate::lox_interpreter::{ast_tools::ASTPrinter, parser::Parser, scanner::Scanner};
+    use std::{fs, path::Path};
+
+    let directory = Path::new(env!("CARGO_MANIFEST_DIR")).join("src/test_files/optimizer");
+    let mut checked = 0;
+    for entry in fs::read_dir(&directory).unwrap() {
+        let path = entry.unwrap().path();
+        if path.extension().is_none_or(|extension| extension != "lox") {
+            continue;
+        }
+
+        let tokens = Scanner::new(fs::read(&path).unwrap())
+            .scan_tokens()
+            .unwrap();
+        let statements = Parser::new(tokens).parse().unwrap();
+        let optimized = Optimizer::new().optimize(&statements).unwrap();
+        let dump = ASTPrinter::new().print_statements(&optimized).unwrap();
+
+        let expected = fs::read_to_string(path.with_extension("ast")).unwrap();
+        assert_eq!(dump, expected, "{}", path.display());
+        checked += 1;
+    }
+    assert!(checked > 0);
+}
+
+#[test]
+fn test_folds_constants_and_drops_dead_code() {
+    use crate::lox_interpreter::{parser::Parser, scanner::Scanner};
+
+    let source = "var x = 2 * 3 + 1;\n\
+                  if (false) print 1;\n\
+                  while (false) print 2;\n\
+                  fun f() { return x; print 3; }";
+    let tokens = Scanner::new(source.into()).scan_tokens().unwrap();
+    let statements = Parser::new(tokens).parse().unwrap();
+    let optimized = Optimizer::new().optimize(&statements).unwrap();
+
+    assert_eq!(optimized.len(), 2);
+    match &optimized[0] {
+        Stmt::Var {
+            initializer:
+                Some(Expr::Literal {
+                    value: Literal::Integer(7),
+                }),
+            ..
+        } => {}
+        other => panic!("Expected x to be folded to 7, got {:?}", other),
+    }
+    match &optimized[1] {
+        Stmt::Function { body, .. } => assert_eq!(body.len(), 1),
+        other => panic!("Expected the function, got {:?}", other),
+    }
+}
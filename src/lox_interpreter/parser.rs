@@ -1,8 +1,13 @@
 // TODO: The error reporting in my version is horrendous, fix it at some point!!
+use std::cell::Cell;
+
 use super::{
-    ast_tools::{Expr, Stmt},
-    error::{report_parse_error, LoxError},
+    ast_tools::{
+        Argument, BindingPattern, Expr, MatchArm, Parameter, Pattern, Stmt, TypeAnnotation,
+    },
+    error::{report_parse_error, report_warning, LoxError},
     token::{Literal, Token, TokenType},
+    typechecker::Type,
 };
 
 pub struct Parser {
@@ -10,11 +15,21 @@ pub struct Parser {
     tokens: Vec<Token>,
     // The current token we are at while parsing.
     current: usize,
+    // Labels of the loops enclosing the statement being parsed, innermost last. Unlabelled loops
+    // are None. Used to reject break and continue that have nowhere to go.
+    loop_labels: Vec<Option<Token>>,
+    // Set whenever an error gets reported so that we don't run a program we couldn't parse.
+    had_error: Cell<bool>,
 }
 
 impl Parser {
     pub fn new(tokens: Vec<Token>) -> Self {
-        Parser { tokens, current: 0 }
+        Parser {
+            tokens,
+            current: 0,
+            loop_labels: Vec::new(),
+            had_error: Cell::new(false),
+        }
     }
 
     pub fn parse(&mut self) -> Result<Vec<Stmt>, LoxError> {
@@ -24,12 +39,17 @@ impl Parser {
             statements.push(self.declaration()?);
         }
 
+        if self.had_error.get() {
+            return Err(LoxError::Parse);
+        }
         Ok(statements)
     }
 
     fn declaration(&mut self) -> Result<Stmt, LoxError> {
         let statement = if self.match_tokens(vec![TokenType::VAR]) {
             self.var_declaration()
+        } else if self.match_tokens(vec![TokenType::CONST]) {
+            self.const_declaration()
         } else if self.match_tokens(vec![TokenType::FUN]) {
             self.function()
         } else {
@@ -47,16 +67,136 @@ impl Parser {
     }
 
     fn var_declaration(&mut self) -> Result<Stmt, LoxError> {
-        let name = self.consume(TokenType::IDENTIFIER, "Expected variable name.")?;
+        let target = self.binding_pattern("Expected variable name.")?;
+        let annotation = self.type_annotation()?;
 
         let initializer = if self.match_tokens(vec![TokenType::EQUAL]) {
             Some(self.expression()?)
         } else {
+            if target.name().is_none() {
+                self.error(
+                    self.peek(),
+                    "Destructuring declaration needs an initializer.",
+                );
+            }
             None
         };
 
         self.consume(TokenType::SEMICOLON, "Expected ';' after variable name.")?;
-        Ok(Stmt::Var { name, initializer })
+        Ok(Stmt::Var {
+            target,
+            annotation,
+            initializer,
+        })
+    }
+
+    // The optional `: Type` after a name.
+    fn type_annotation(&mut self) -> Result<Option<TypeAnnotation>, LoxError> {
+        if !self.match_tokens(vec![TokenType::COLON]) {
+            return Ok(None);
+        }
+
+        let name = self.consume(TokenType::IDENTIFIER, "Expected type name after ':'.")?;
+        match Type::from_name(&name.lexeme) {
+            Some(ty) => Ok(Some(TypeAnnotation { name, ty })),
+            None => Err(self.error(&name, &format!("Unknown type '{}'.", name.lexeme))),
+        }
+    }
+
+    // A name, `[a, b, ...rest]` or `{x, y: other}`, patterns can be nested.
+    fn binding_pattern(&mut self, message: &str) -> Result<BindingPattern, LoxError> {
+        if self.match_tokens(vec![TokenType::LEFT_BRACKET]) {
+            let bracket = self.previous().clone();
+            let mut elements = Vec::new();
+            let mut rest = None;
+            if !self.check(TokenType::RIGHT_BRACKET) {
+                loop {
+                    if self.match_tokens(vec![TokenType::DOT_DOT_DOT]) {
+                        rest = Some(
+                            self.consume(TokenType::IDENTIFIER, "Expected name after '...'.")?,
+                        );
+                        // NOTE: Nothing can follow the rest, the closing bracket is expected next.
+                        break;
+                    }
+                    elements.push(self.binding_pattern("Expected name in list pattern.")?);
+                    if !self.match_tokens(vec![TokenType::COMMA]) {
+                        break;
+                    }
+                }
+            }
+            self.consume(TokenType::RIGHT_BRACKET, "Expected ']' after list pattern.")?;
+
+            Ok(BindingPattern::List {
+                bracket,
+                elements,
+                rest,
+            })
+        } else if self.match_tokens(vec![TokenType::LEFT_BRACE]) {
+            let brace = self.previous().clone();
+            let mut fields = Vec::new();
+            if !self.check(TokenType::RIGHT_BRACE) {
+                loop {
+                    let key =
+                        self.consume(TokenType::IDENTIFIER, "Expected key in map pattern.")?;
+                    let field = if self.match_tokens(vec![TokenType::COLON]) {
+                        self.binding_pattern("Expected name after ':'.")?
+                    } else {
+                        BindingPattern::Name(key.clone())
+                    };
+                    fields.push((key, field));
+                    if !self.match_tokens(vec![TokenType::COMMA]) {
+                        break;
+                    }
+                }
+            }
+            self.consume(TokenType::RIGHT_BRACE, "Expected '}' after map pattern.")?;
+
+            Ok(BindingPattern::Map { brace, fields })
+        } else {
+            Ok(BindingPattern::Name(
+                self.consume(TokenType::IDENTIFIER, message)?,
+            ))
+        }
+    }
+
+    // Looks past a bracketed or braced group for a '=', which tells a destructuring assignment
+    // like `[a, b] = [b, a]` apart from a list or a block.
+    fn is_destructuring_assignment(&self) -> bool {
+        if !self.check(TokenType::LEFT_BRACKET) && !self.check(TokenType::LEFT_BRACE) {
+            return false;
+        }
+
+        let mut depth = 0;
+        for (offset, token) in self.tokens[self.current..].iter().enumerate() {
+            match token.token_type {
+                TokenType::LEFT_BRACKET | TokenType::LEFT_BRACE | TokenType::LEFT_PAREN => {
+                    depth += 1
+                }
+                TokenType::RIGHT_BRACKET | TokenType::RIGHT_BRACE | TokenType::RIGHT_PAREN => {
+                    depth -= 1;
+                    if depth == 0 {
+                        return self.check_at(offset + 1, TokenType::EQUAL);
+                    }
+                }
+                TokenType::EOF => return false,
+                _ => {}
+            }
+        }
+        false
+    }
+
+    fn const_declaration(&mut self) -> Result<Stmt, LoxError> {
+        let name = self.consume(TokenType::IDENTIFIER, "Expected constant name.")?;
+        let annotation = self.type_annotation()?;
+        self.consume(TokenType::EQUAL, "Expected '=' after constant name.")?;
+        let initializer = self.expression()?;
+
+        self.consume(TokenType::SEMICOLON, "Expected ';' after constant value.")?;
+        Ok(Stmt::Const {
+            name,
+            annotation,
+            initializer,
+        })
     }
 
     fn expression(&mut self) -> Result<Expr, LoxError> {
@@ -88,6 +228,14 @@ impl Parser {
         self.peek().token_type == token_type
     }
 
+    // Looks `offset` tokens ahead of the current one without consuming anything.
+    fn check_at(&self, offset: usize, token_type: TokenType) -> bool {
+        match self.tokens.get(self.current + offset) {
+            Some(token) => token.token_type == token_type,
+            None => false,
+        }
+    }
+
     fn match_tokens(&mut self, token_types: Vec<TokenType>) -> bool {
         for token_type in token_types {
             if self.check(token_type) {
@@ -124,7 +272,7 @@ impl Parser {
     }
 
     fn comparison(&mut self) -> Result<Expr, LoxError> {
-        let mut expr = self.term()?;
+        let mut expr = self.bitwise_or()?;
 
         while self.match_tokens(vec![
             TokenType::GREATER,
@@ -133,7 +281,49 @@ impl Parser {
             TokenType::LESS_EQUAL,
         ]) {
             let operator = self.previous().clone();
-            let right = self.term()?;
+            let right = self.bitwise_or()?;
+            expr = Expr::Binary {
+                left: Box::new(expr),
+                operator,
+                right: Box::new(right),
+            };
+        }
+
+        Ok(expr)
+    }
+
+    // NOTE: Bitwise operators bind tighter than comparisons, so `a & 1 == 0` does what it reads
+    // like instead of the C behaviour.
+    fn bitwise_or(&mut self) -> Result<Expr, LoxError> {
+        self.left_associative(vec![TokenType::PIPE], Self::bitwise_xor)
+    }
+
+    fn bitwise_xor(&mut self) -> Result<Expr, LoxError> {
+        self.left_associative(vec![TokenType::CARET], Self::bitwise_and)
+    }
+
+    fn bitwise_and(&mut self) -> Result<Expr, LoxError> {
+        self.left_associative(vec![TokenType::AMPERSAND], Self::shift)
+    }
+
+    fn shift(&mut self) -> Result<Expr, LoxError> {
+        self.left_associative(
+            vec![TokenType::LESS_LESS, TokenType::GREATER_GREATER],
+            Self::term,
+        )
+    }
+
+    // Parses `operand (operator operand)*` folding the results to the left.
+    fn left_associative(
+        &mut self,
+        operators: Vec<TokenType>,
+        operand: fn(&mut Self) -> Result<Expr, LoxError>,
+    ) -> Result<Expr, LoxError> {
+        let mut expr = operand(self)?;
+
+        while self.match_tokens(operators.clone()) {
+            let operator = self.previous().clone();
+            let right = operand(self)?;
             expr = Expr::Binary {
                 left: Box::new(expr),
                 operator,
@@ -163,7 +353,12 @@ impl Parser {
     fn factor(&mut self) -> Result<Expr, LoxError> {
         let mut expr = self.unary()?;
 
-        while self.match_tokens(vec![TokenType::SLASH, TokenType::STAR]) {
+        while self.match_tokens(vec![
+            TokenType::SLASH,
+            TokenType::STAR,
+            TokenType::PERCENT,
+            TokenType::TILDE_SLASH,
+        ]) {
             let operator = self.previous().clone();
             let right = self.unary()?;
             expr = Expr::Binary {
@@ -177,6 +372,20 @@ impl Parser {
     }
 
     fn unary(&mut self) -> Result<Expr, LoxError> {
+        if self.match_tokens(vec![TokenType::PLUS_PLUS, TokenType::MINUS_MINUS]) {
+            let operator = self.previous().clone();
+            let target = self.unary()?;
+
+            if !Self::is_assignment_target(&target) {
+                return Err(self.error(&operator, "Invalid assignment target."));
+            }
+            return Ok(Expr::Increment {
+                target: Box::new(target),
+                operator,
+                prefix: true,
+            });
+        }
+
         if self.match_tokens(vec![TokenType::BANG, TokenType::MINUS]) {
             let operator = self.previous().clone();
             let right = self.unary()?;
@@ -186,7 +395,44 @@ impl Parser {
             });
         }
 
-        self.call()
+        self.power()
+    }
+
+    // Exponentiation is right associative and binds tighter than a unary on its left, so
+    // `-2 ** 2` is -4 and `2 ** 3 ** 2` is 2 ** 9.
+    fn power(&mut self) -> Result<Expr, LoxError> {
+        let expr = self.postfix()?;
+
+        if self.match_tokens(vec![TokenType::STAR_STAR]) {
+            let operator = self.previous().clone();
+            let right = self.unary()?;
+            return Ok(Expr::Binary {
+                left: Box::new(expr),
+                operator,
+                right: Box::new(right),
+            });
+        }
+
+        Ok(expr)
+    }
+
+    fn postfix(&mut self) -> Result<Expr, LoxError> {
+        let expr = self.call()?;
+
+        if self.match_tokens(vec![TokenType::PLUS_PLUS, TokenType::MINUS_MINUS]) {
+            let operator = self.previous().clone();
+
+            if !Self::is_assignment_target(&expr) {
+                return Err(self.error(&operator, "Invalid assignment target."));
+            }
+            return Ok(Expr::Increment {
+                target: Box::new(expr),
+                operator,
+                prefix: false,
+            });
+        }
+
+        Ok(expr)
     }
 
     fn call(&mut self) -> Result<Expr, LoxError> {
@@ -196,6 +442,24 @@ impl Parser {
         loop {
             if self.match_tokens(vec![TokenType::LEFT_PAREN]) {
                 expr = self.finish_call(expr)?;
+            } else if self.match_tokens(vec![TokenType::LEFT_BRACKET]) {
+                let bracket = self.previous().clone();
+                let index = self.expression()?;
+                self.consume(TokenType::RIGHT_BRACKET, "Expected ']' after index.")?;
+                expr = Expr::Index {
+                    object: Box::new(expr),
+                    bracket,
+                    index: Box::new(index),
+                };
+            } else if self.match_tokens(vec![TokenType::DOT, TokenType::QUESTION_DOT]) {
+                let optional = self.previous().token_type == TokenType::QUESTION_DOT;
+                let name =
+                    self.consume(TokenType::IDENTIFIER, "Expected property name after '.'.")?;
+                expr = Expr::Get {
+                    object: Box::new(expr),
+                    name,
+                    optional,
+                };
             } else {
                 break;
             }
@@ -217,16 +481,11 @@ impl Parser {
                 // TODO: Streamline use of None NIL and NULL.
                 value: Literal::None,
             },
-            TokenType::STRING => Expr::Literal {
-                value: Literal::String(token.lexeme.clone()),
+            // NOTE: The scanner already worked out the value, the string without its quotes and
+            // whether a number is an integer or a float, so we reuse its literal.
+            TokenType::STRING | TokenType::NUMBER => Expr::Literal {
+                value: token.literal.clone(),
             },
-            TokenType::NUMBER => {
-                Expr::Literal {
-                    // TODO: Check the performance implication of this. Maybe it'd be better to store
-                    // a copy of the float in the enum itself.
-                    value: Literal::Float(token.lexeme.parse::<f64>().unwrap()),
-                }
-            }
             TokenType::IDENTIFIER => Expr::Variable {
                 name: self.peek().clone(),
             },
@@ -244,6 +503,51 @@ impl Parser {
                     expression: Box::new(expr),
                 }
             }
+            TokenType::LEFT_BRACKET => {
+                self.consume(TokenType::LEFT_BRACKET, "Expected '['.")?;
+                let mut elements: Vec<Expr> = Vec::new();
+                if !self.check(TokenType::RIGHT_BRACKET) {
+                    loop {
+                        elements.push(self.expression()?);
+                        if !self.match_tokens(vec![TokenType::COMMA]) {
+                            break;
+                        }
+                    }
+                }
+
+                // NOTE: Same as the grouping above, the closing bracket is consumed by the
+                // advance at the end.
+                let token = self.peek();
+                if token.token_type != TokenType::RIGHT_BRACKET {
+                    return Err(self.error(token, "Expected ']' after list elements."));
+                }
+                Expr::List { elements }
+            }
+            TokenType::LEFT_BRACE => {
+                let brace = self.advance().clone();
+                let mut entries: Vec<(Token, Expr)> = Vec::new();
+                if !self.check(TokenType::RIGHT_BRACE) {
+                    loop {
+                        let key = if self.check(TokenType::STRING) {
+                            self.advance().clone()
+                        } else {
+                            self.consume(TokenType::IDENTIFIER, "Expected map key.")?
+                        };
+                        self.consume(TokenType::COLON, "Expected ':' after map key.")?;
+                        entries.push((key, self.expression()?));
+                        if !self.match_tokens(vec![TokenType::COMMA]) {
+                            break;
+                        }
+                    }
+                }
+
+                // NOTE: Same as lists, the closing brace is consumed by the advance at the end.
+                let token = self.peek();
+                if token.token_type != TokenType::RIGHT_BRACE {
+                    return Err(self.error(token, "Expected '}' after map entries."));
+                }
+                Expr::Map { brace, entries }
+            }
 
             _ => {
                 //println!("Error at token: {:#?}", token);
@@ -264,6 +568,7 @@ impl Parser {
 
     pub fn error(&self, token: &Token, message: &str) -> LoxError {
         report_parse_error(&token, message);
+        self.had_error.set(true);
         LoxError::Parse
     }
 
@@ -281,9 +586,13 @@ impl Parser {
                 TokenType::CLASS
                 | TokenType::FUN
                 | TokenType::VAR
+                | TokenType::CONST
                 | TokenType::FOR
                 | TokenType::IF
+                | TokenType::MATCH
                 | TokenType::WHILE
+                | TokenType::DO
+                | TokenType::LOOP
                 | TokenType::PRINT
                 | TokenType::RETURN => return,
                 _ => self.advance(),
@@ -292,31 +601,104 @@ impl Parser {
     }
 
     fn statement(&mut self) -> Result<Stmt, LoxError> {
-        if self.match_tokens(vec![TokenType::BREAK]) {
+        if self.check(TokenType::IDENTIFIER) && self.check_at(1, TokenType::COLON) {
+            self.labelled_statement()
+        } else if self.match_tokens(vec![TokenType::BREAK]) {
+            let (keyword, label) = self.loop_jump("break")?;
             self.consume(TokenType::SEMICOLON, "Expected ';' after break")?;
-            Ok(Stmt::Break)
+            Ok(Stmt::Break { keyword, label })
         } else if self.match_tokens(vec![TokenType::CONTINUE]) {
+            let (keyword, label) = self.loop_jump("continue")?;
             self.consume(TokenType::SEMICOLON, "Expected ';' after continue.")?;
-            Ok(Stmt::Continue)
+            Ok(Stmt::Continue { keyword, label })
         } else if self.match_tokens(vec![TokenType::IF]) {
             self.if_statement()
+        } else if self.match_tokens(vec![TokenType::MATCH]) {
+            self.match_statement()
         } else if self.match_tokens(vec![TokenType::PRINT]) {
             self.print_statement()
         } else if self.match_tokens(vec![TokenType::RETURN]) {
             self.return_statement()
+        } else if self.is_destructuring_assignment() {
+            // NOTE: Has to come before blocks, `{x, y} = point;` starts with a brace as well.
+            self.expression_statement()
         } else if self.match_tokens(vec![TokenType::LEFT_BRACE]) {
             Ok(Stmt::Block {
                 statements: self.block()?,
             })
         } else if self.match_tokens(vec![TokenType::WHILE]) {
-            self.while_statement()
+            self.while_statement(None)
         } else if self.match_tokens(vec![TokenType::FOR]) {
-            self.for_statement()
+            self.for_statement(None)
+        } else if self.match_tokens(vec![TokenType::DO]) {
+            self.do_while_statement(None)
+        } else if self.match_tokens(vec![TokenType::LOOP]) {
+            self.loop_statement(None)
         } else {
             self.expression_statement()
         }
     }
 
+    // `outer: while (...) { ... break outer; }`, only loops can be labelled.
+    fn labelled_statement(&mut self) -> Result<Stmt, LoxError> {
+        let label = self.advance().clone();
+        self.consume(TokenType::COLON, "Expected ':' after label.")?;
+
+        if self.match_tokens(vec![TokenType::WHILE]) {
+            self.while_statement(Some(label))
+        } else if self.match_tokens(vec![TokenType::FOR]) {
+            self.for_statement(Some(label))
+        } else if self.match_tokens(vec![TokenType::DO]) {
+            self.do_while_statement(Some(label))
+        } else if self.match_tokens(vec![TokenType::LOOP]) {
+            self.loop_statement(Some(label))
+        } else {
+            Err(self.error(self.peek(), "Expected a loop after label."))
+        }
+    }
+
+    // Checks that a break or continue, whose keyword was just consumed, has a loop to jump to
+    // and reads its optional label.
+    fn loop_jump(&mut self, keyword_name: &str) -> Result<(Token, Option<Token>), LoxError> {
+        let keyword = self.previous().clone();
+        let label = if self.check(TokenType::IDENTIFIER) {
+            Some(self.advance().clone())
+        } else {
+            None
+        };
+
+        if self.loop_labels.is_empty() {
+            return Err(self.error(
+                &keyword,
+                &format!("Can't use '{}' outside of a loop.", keyword_name),
+            ));
+        }
+
+        if let Some(label) = &label {
+            let is_enclosing = self.loop_labels.iter().any(|enclosing| {
+                enclosing
+                    .as_ref()
+                    .is_some_and(|enclosing| enclosing.lexeme == label.lexeme)
+            });
+            if !is_enclosing {
+                return Err(self.error(
+                    label,
+                    &format!("No enclosing loop with label '{}'.", label.lexeme),
+                ));
+            }
+        }
+
+        Ok((keyword, label))
+    }
+
+    // Parses a loop body while keeping track of the loop so break and continue can find it.
+    fn loop_body(&mut self, label: &Option<Token>) -> Result<Stmt, LoxError> {
+        self.loop_labels.push(label.clone());
+        let body = self.statement();
+        self.loop_labels.pop();
+        body
+    }
+
     fn if_statement(&mut self) -> Result<Stmt, LoxError> {
         self.consume(TokenType::LEFT_PAREN, "Expected '(' after if.")?;
         let condition = self.expression()?;
@@ -341,6 +723,115 @@ impl Parser {
         }
     }
 
+    fn match_statement(&mut self) -> Result<Stmt, LoxError> {
+        let keyword = self.previous().clone();
+        self.consume(TokenType::LEFT_PAREN, "Expected '(' after match.")?;
+        let subject = self.expression()?;
+        self.consume(TokenType::RIGHT_PAREN, "Expected ')' after match subject.")?;
+        self.consume(TokenType::LEFT_BRACE, "Expected '{' before match arms.")?;
+
+        let mut arms: Vec<MatchArm> = Vec::new();
+        while !self.check(TokenType::RIGHT_BRACE) && !self.is_at_end() {
+            self.consume(TokenType::CASE, "Expected 'case' before match arm.")?;
+
+            let mut patterns: Vec<Pattern> = Vec::new();
+            loop {
+                patterns.push(self.pattern()?);
+                if !self.match_tokens(vec![TokenType::COMMA]) {
+                    break;
+                }
+            }
+
+            let guard = if self.match_tokens(vec![TokenType::IF]) {
+                Some(self.expression()?)
+            } else {
+                None
+            };
+            self.consume(TokenType::FAT_ARROW, "Expected '=>' after case patterns.")?;
+            let body = self.statement()?;
+
+            arms.push(MatchArm {
+                patterns,
+                guard,
+                body,
+            });
+        }
+        self.consume(TokenType::RIGHT_BRACE, "Expected '}' after match arms.")?;
+
+        if !Self::is_exhaustive(&arms) {
+            report_warning(
+                &keyword,
+                "Match has no default arm, values that no case covers are ignored.",
+            );
+        }
+
+        Ok(Stmt::Match {
+            keyword,
+            subject,
+            arms,
+        })
+    }
+
+    fn pattern(&mut self) -> Result<Pattern, LoxError> {
+        if self.check(TokenType::IDENTIFIER) {
+            let name = self.advance().clone();
+            if name.lexeme == "_" {
+                return Ok(Pattern::Wildcard);
+            }
+            return Ok(Pattern::Binding(name));
+        }
+
+        let start = self.pattern_literal()?;
+        if self.match_tokens(vec![TokenType::DOT_DOT, TokenType::DOT_DOT_EQUAL]) {
+            let inclusive = self.previous().token_type == TokenType::DOT_DOT_EQUAL;
+            let end = self.pattern_literal()?;
+            return Ok(Pattern::Range {
+                start,
+                end,
+                inclusive,
+            });
+        }
+
+        Ok(Pattern::Literal(start))
+    }
+
+    fn pattern_literal(&mut self) -> Result<Literal, LoxError> {
+        // NOTE: Patterns aren't expressions, so a negative number has to be handled here.
+        let negative = self.match_tokens(vec![TokenType::MINUS]);
+        let token = self.advance().clone();
+
+        match (&token.token_type, &token.literal, negative) {
+            (TokenType::NUMBER, Literal::Integer(integer), true) => Ok(Literal::Integer(-integer)),
+            (TokenType::NUMBER, Literal::Float(float), true) => Ok(Literal::Float(-float)),
+            (TokenType::NUMBER | TokenType::STRING, literal, false) => Ok(literal.clone()),
+            (TokenType::TRUE, _, false) => Ok(Literal::Boolean(true)),
+            (TokenType::FALSE, _, false) => Ok(Literal::Boolean(false)),
+            (TokenType::NONE, _, false) => Ok(Literal::None),
+            _ => Err(self.error(&token, "Expected a pattern.")),
+        }
+    }
+
+    // A match is exhaustive when some unguarded arm matches anything, or when the unguarded arms
+    // cover both booleans.
+    fn is_exhaustive(arms: &[MatchArm]) -> bool {
+        let unguarded_patterns = arms
+            .iter()
+            .filter(|arm| arm.guard.is_none())
+            .flat_map(|arm| arm.patterns.iter());
+
+        let (mut has_true, mut has_false) = (false, false);
+        for pattern in unguarded_patterns {
+            match pattern {
+                Pattern::Wildcard | Pattern::Binding(_) => return true,
+                Pattern::Literal(Literal::Boolean(true)) => has_true = true,
+                Pattern::Literal(Literal::Boolean(false)) => has_false = true,
+                _ => {}
+            }
+        }
+
+        has_true && has_false
+    }
+
     fn block(&mut self) -> Result<Vec<Stmt>, LoxError> {
         let mut statements: Vec<Stmt> = Vec::new();
 
@@ -366,7 +857,18 @@ impl Parser {
     }
 
     fn assignment(&mut self) -> Result<Expr, LoxError> {
-        let expr = self.or()?;
+        if self.is_destructuring_assignment() {
+            let pattern = self.binding_pattern("Expected assignment target.")?;
+            let equals = self.consume(TokenType::EQUAL, "Expected '=' after pattern.")?;
+            let value = self.assignment()?;
+            return Ok(Expr::DestructureAssign {
+                pattern,
+                equals,
+                value: Box::new(value),
+            });
+        }
+
+        let expr = self.conditional()?;
 
         if self.match_tokens(vec![TokenType::EQUAL]) {
             // NOTE: If you change the ordering it complains that immuatble borrow occurs before
@@ -385,58 +887,150 @@ impl Parser {
                         value: Box::new(value),
                     })
                 }
+                Expr::Index {
+                    object,
+                    bracket,
+                    index,
+                } => {
+                    return Ok(Expr::SetIndex {
+                        object,
+                        bracket,
+                        index,
+                        value: Box::new(value),
+                    })
+                }
                 _ => return Err(self.error(&equals, "Invalid assignment target.")),
             }
+        } else if self.match_tokens(vec![
+            TokenType::PLUS_EQUAL,
+            TokenType::MINUS_EQUAL,
+            TokenType::STAR_EQUAL,
+            TokenType::SLASH_EQUAL,
+        ]) {
+            let operator = self.previous().clone();
+            let value = self.assignment()?;
+
+            if !Self::is_assignment_target(&expr) {
+                return Err(self.error(&operator, "Invalid assignment target."));
+            }
+            return Ok(Expr::CompoundAssign {
+                target: Box::new(expr),
+                operator,
+                value: Box::new(value),
+            });
         }
 
         Ok(expr)
     }
 
-    fn or(&mut self) -> Result<Expr, LoxError> {
-        let expr = self.and()?;
-
-        while self.match_tokens(vec![TokenType::OR]) {
-            let operator = self.previous().clone();
-            let right = Box::new(self.and()?);
+    // Expressions that can appear on the left of `=`, `+=` and friends, or next to `++`/`--`.
+    fn is_assignment_target(expr: &Expr) -> bool {
+        matches!(expr, Expr::Variable { .. } | Expr::Index { .. })
+    }
 
-            return Ok(Expr::Logical {
-                left: Box::new(expr),
-                operator,
-                right,
+    // The else branch recurses into conditional, which makes `a ? b : c ? d : e` right
+    // associative.
+    fn conditional(&mut self) -> Result<Expr, LoxError> {
+        let condition = self.coalesce()?;
+
+        if self.match_tokens(vec![TokenType::QUESTION]) {
+            let then_branch = self.expression()?;
+            self.consume(
+                TokenType::COLON,
+                "Expected ':' after then branch of conditional expression.",
+            )?;
+            let else_branch = self.conditional()?;
+
+            return Ok(Expr::Conditional {
+                condition: Box::new(condition),
+                then_branch: Box::new(then_branch),
+                else_branch: Box::new(else_branch),
             });
         }
 
-        return Ok(expr);
+        Ok(condition)
+    }
+
+    fn coalesce(&mut self) -> Result<Expr, LoxError> {
+        self.logical(vec![TokenType::QUESTION_QUESTION], Self::or)
+    }
+
+    fn or(&mut self) -> Result<Expr, LoxError> {
+        self.logical(vec![TokenType::OR], Self::and)
     }
 
     fn and(&mut self) -> Result<Expr, LoxError> {
-        let expr = self.equality()?;
+        self.logical(vec![TokenType::AND], Self::equality)
+    }
 
-        while self.match_tokens(vec![TokenType::AND]) {
-            let operator = self.previous().clone();
-            let right = Box::new(self.equality()?);
+    // Same as left_associative but builds short circuiting Logical expressions.
+    fn logical(
+        &mut self,
+        operators: Vec<TokenType>,
+        operand: fn(&mut Self) -> Result<Expr, LoxError>,
+    ) -> Result<Expr, LoxError> {
+        let mut expr = operand(self)?;
 
-            return Ok(Expr::Logical {
+        while self.match_tokens(operators.clone()) {
+            let operator = self.previous().clone();
+            let right = operand(self)?;
+            expr = Expr::Logical {
                 left: Box::new(expr),
                 operator,
-                right,
-            });
+                right: Box::new(right),
+            };
         }
 
         Ok(expr)
     }
 
-    fn while_statement(&mut self) -> Result<Stmt, LoxError> {
+    fn while_statement(&mut self, label: Option<Token>) -> Result<Stmt, LoxError> {
         self.consume(TokenType::LEFT_PAREN, "Expect '(' after 'while'.")?;
         let condition = self.expression()?;
         self.consume(TokenType::RIGHT_PAREN, "Expect ')' after condition.")?;
-        let body = Box::new(self.statement()?);
-        Ok(Stmt::While { condition, body })
+        let body = Box::new(self.loop_body(&label)?);
+        Ok(Stmt::While {
+            condition,
+            body,
+            increment: None,
+            label,
+        })
+    }
+
+    fn do_while_statement(&mut self, label: Option<Token>) -> Result<Stmt, LoxError> {
+        let body = Box::new(self.loop_body(&label)?);
+        self.consume(TokenType::WHILE, "Expect 'while' after 'do' body.")?;
+        self.consume(TokenType::LEFT_PAREN, "Expect '(' after 'while'.")?;
+        let condition = self.expression()?;
+        self.consume(TokenType::RIGHT_PAREN, "Expect ')' after condition.")?;
+        self.consume(
+            TokenType::SEMICOLON,
+            "Expected ';' after do while condition.",
+        )?;
+
+        Ok(Stmt::DoWhile {
+            body,
+            condition,
+            label,
+        })
     }
 
-    fn for_statement(&mut self) -> Result<Stmt, LoxError> {
+    fn loop_statement(&mut self, label: Option<Token>) -> Result<Stmt, LoxError> {
+        let body = Box::new(self.loop_body(&label)?);
+        Ok(Stmt::Loop { body, label })
+    }
+
+    fn for_statement(&mut self, label: Option<Token>) -> Result<Stmt, LoxError> {
         self.consume(TokenType::LEFT_PAREN, "Expect '(' after 'for'.")?;
 
+        // `for (x in xs)` and `for (var x in xs)` both declare x fresh for every iteration.
+        let var_offset = if self.check(TokenType::VAR) { 1 } else { 0 };
+        if self.check_at(var_offset, TokenType::IDENTIFIER)
+            && self.check_at(var_offset + 1, TokenType::IN)
+        {
+            return self.for_in_statement(label);
+        }
+
         // Check if we got an initializtion, i.e. var x = 0 or something of the like.
         let initializer = if self.match_tokens(vec![TokenType::SEMICOLON]) {
             None
@@ -463,31 +1057,23 @@ impl Parser {
         self.consume(TokenType::RIGHT_PAREN, "Expected ')' after for clauses.")?;
 
         // For loop body.
-        let mut body = self.statement()?;
+        let mut body = self.loop_body(&label)?;
 
         // NOTE: Now begins the fun part. We do not use a new Stmt visitor or something for this,
         // we just convert it to the matching while loop and we already got while in place.
         // What we do is:
-        //  1. Get the increment statement
-        //  2. Make a block with the body and increment statement, essentially attach the increment
-        //     statement to the for block.
-        //  3. Make a while statement with the condition and the body the we got after
-        //     concatenation from step 2.
-        //  4. Get the initialization statement.
-        //  5. Create a new Block statement that does the initialization once and then executes the
+        //  1. Make a while statement with the condition, the body and the increment. The
+        //     increment is kept separate from the body so that continue still runs it.
+        //  2. Get the initialization statement.
+        //  3. Create a new Block statement that does the initialization once and then executes the
         //     While loop.
-        if let Some(incr) = increment {
-            let increment_statement = Stmt::Expression { expression: incr };
-            body = Stmt::Block {
-                statements: vec![body, increment_statement],
-            }
-        }
-
         body = Stmt::While {
             condition: condition.unwrap_or(Expr::Literal {
                 value: Literal::Boolean(true),
             }),
             body: Box::new(body),
+            increment,
+            label,
         };
 
         if let Some(initializon_statement) = initializer {
@@ -499,8 +1085,24 @@ impl Parser {
         Ok(body)
     }
 
+    fn for_in_statement(&mut self, label: Option<Token>) -> Result<Stmt, LoxError> {
+        self.match_tokens(vec![TokenType::VAR]);
+        let variable = self.consume(TokenType::IDENTIFIER, "Expected loop variable name.")?;
+        self.consume(TokenType::IN, "Expected 'in' after loop variable.")?;
+        let iterable = self.expression()?;
+        self.consume(TokenType::RIGHT_PAREN, "Expected ')' after for clauses.")?;
+        let body = Box::new(self.loop_body(&label)?);
+
+        Ok(Stmt::ForIn {
+            variable,
+            iterable,
+            body,
+            label,
+        })
+    }
+
     fn finish_call(&mut self, callee: Expr) -> Result<Expr, LoxError> {
-        let mut arguments: Vec<Expr> = Vec::new();
+        let mut arguments: Vec<Argument> = Vec::new();
 
         //println!("Previous Fn Token: {:#?}", self.previous());
         if !self.check(TokenType::RIGHT_PAREN) {
@@ -512,7 +1114,7 @@ impl Parser {
 
                 //println!("Current Before Call: {}", self.current);
                 //println!("Current Token: {:?}", self.tokens[self.current]);
-                arguments.push(self.expression()?);
+                arguments.push(self.argument(&arguments)?);
 
                 //println!("Current Token After Call: {:?}", self.tokens[self.current]);
                 if !self.match_tokens(vec![TokenType::COMMA]) {
@@ -532,18 +1134,105 @@ impl Parser {
         })
     }
 
+    // `value` or `name: value`, keyword arguments have to come after all the positional ones.
+    fn argument(&mut self, previous: &[Argument]) -> Result<Argument, LoxError> {
+        let name = if self.check(TokenType::IDENTIFIER) && self.check_at(1, TokenType::COLON) {
+            let name = self.advance().clone();
+            self.advance();
+            Some(name)
+        } else {
+            None
+        };
+
+        match &name {
+            Some(name) => {
+                let repeated = previous
+                    .iter()
+                    .filter_map(|argument| argument.name.as_ref())
+                    .any(|other| other.lexeme == name.lexeme);
+                if repeated {
+                    self.error(
+                        name,
+                        &format!("Keyword argument '{}' given more than once.", name.lexeme),
+                    );
+                }
+            }
+            None if previous.iter().any(|argument| argument.name.is_some()) => {
+                self.error(
+                    self.peek(),
+                    "Positional arguments must come before keyword arguments.",
+                );
+            }
+            None => {}
+        }
+
+        let value = self.expression()?;
+        Ok(Argument { name, value })
+    }
+
+    // `a`, `a = default` or `...a`.
+    fn parameter(&mut self, previous: &[Parameter]) -> Result<Parameter, LoxError> {
+        if previous.last().is_some_and(|param| param.is_rest) {
+            self.error(self.peek(), "Rest parameter has to be the last one.");
+        }
+
+        let is_rest = self.match_tokens(vec![TokenType::DOT_DOT_DOT]);
+        let target = if is_rest {
+            BindingPattern::Name(self.consume(TokenType::IDENTIFIER, "Expected paramater.")?)
+        } else {
+            self.binding_pattern("Expected paramater.")?
+        };
+        for name in target.names() {
+            let duplicate = previous
+                .iter()
+                .flat_map(|param| param.target.names())
+                .any(|other| other.lexeme == name.lexeme);
+            if duplicate {
+                self.error(name, &format!("Duplicate parameter '{}'.", name.lexeme));
+            }
+        }
+        let annotation = self.type_annotation()?;
+
+        let default = if self.match_tokens(vec![TokenType::EQUAL]) {
+            if is_rest {
+                self.error(
+                    self.previous(),
+                    "Rest parameter can't have a default value.",
+                );
+            }
+            Some(self.expression()?)
+        } else {
+            None
+        };
+
+        if default.is_none() && !is_rest && previous.iter().any(|param| param.default.is_some()) {
+            self.error(
+                self.previous(),
+                "Parameter without a default can't come after one with a default.",
+            );
+        }
+
+        Ok(Parameter {
+            target,
+            annotation,
+            default,
+            is_rest,
+        })
+    }
+
     // This is not just declaration it seems.
     fn function(&mut self) -> Result<Stmt, LoxError> {
         let name = self.consume(TokenType::IDENTIFIER, "Expect function name.")?;
 
         self.consume(TokenType::LEFT_PAREN, "Expected '(' after functoin name.")?;
-        let mut paramaters: Vec<Token> = Vec::new();
+        let mut paramaters: Vec<Parameter> = Vec::new();
         if !self.check(TokenType::RIGHT_PAREN) {
             loop {
                 if paramaters.len() >= 255 {
                     self.error(self.peek(), "Cannot have more than 255 params.");
                 }
-                paramaters.push(self.consume(TokenType::IDENTIFIER, "Expected paramater.")?);
+                let paramater = self.parameter(&paramaters)?;
+                paramaters.push(paramater);
 
                 if !self.match_tokens(vec![TokenType::COMMA]) {
                     break;
@@ -551,15 +1240,22 @@ impl Parser {
             }
         }
         self.consume(TokenType::RIGHT_PAREN, "Expected ')' after paramaters")?;
+        let return_type = self.type_annotation()?;
         self.consume(
             TokenType::LEFT_BRACE,
             "Expected '{' after function declaration.",
         )?;
-        let body = self.block()?;
+        // NOTE: A function body starts outside of any loop, a break in there can't reach a loop
+        // around the declaration.
+        let enclosing_loops = std::mem::take(&mut self.loop_labels);
+        let body = self.block();
+        self.loop_labels = enclosing_loops;
+        let body = body?;
         //println!("{:?}", self.peek());
         Ok(Stmt::Function {
             name,
             paramaters,
+            return_type,
             body,
         })
     }
@@ -576,3 +1272,119 @@ impl Parser {
         Ok(Stmt::Return { keyword, value })
     }
 }
+
+#[test]
+fn test_loop_jumps_are_checked() {
+    use crate::lox_interpreter::scanner::Scanner;
+
+    let parse = |source: &str| {
+        let tokens = Scanner::new(source.into()).scan_tokens().unwrap();
+        Parser::new(tokens).parse()
+    };
+
+    assert!(parse("outer: while (true) { for (x in y) { break outer; } continue; }").is_ok());
+    assert!(parse("break;").is_err());
+    assert!(parse("while (true) { fun f() { continue; } }").is_err());
+    assert!(parse("while (true) { break inner; }").is_err());
+}
+
+#[test]
+fn test_destructuring_patterns() {
+    use crate::lox_interpreter::scanner::Scanner;
+
+    let parse = |source: &str| {
+        let tokens = Scanner::new(source.into()).scan_tokens().unwrap();
+        Parser::new(tokens).parse()
+    };
+
+    let statements = parse("[a, b] = [b, a]; {x, y: [z]} = point; [1, 2][0] = 3;").unwrap();
+    assert!(matches!(
+        statements[0],
+        Stmt::Expression {
+            expression: Expr::DestructureAssign { .. }
+        }
+    ));
+    assert!(matches!(
+        statements[1],
+        Stmt::Expression {
+            expression: Expr::DestructureAssign { .. }
+        }
+    ));
+    assert!(matches!(
+        statements[2],
+        Stmt::Expression {
+            expression: Expr::SetIndex { .. }
+        }
+    ));
+
+    assert!(parse("var [a, ...rest] = list; fun f([a], {b}) {}").is_ok());
+    assert!(parse("var [a, b];").is_err());
+    assert!(parse("var [...rest, a] = list;").is_err());
+}
+
+#[test]
+fn test_compound_assignment_and_increments() {
+    use crate::lox_interpreter::scanner::Scanner;
+
+    let parse = |source: &str| {
+        let tokens = Scanner::new(source.into()).scan_tokens().unwrap();
+        Parser::new(tokens).parse()
+    };
+
+    let statements = parse("x += 1; x -= 1; list[0] *= 2; list[0] /= 2; ++x; x--;").unwrap();
+    for statement in &statements[..4] {
+        assert!(matches!(
+            statement,
+            Stmt::Expression {
+                expression: Expr::CompoundAssign { .. }
+            }
+        ));
+    }
+    assert!(matches!(
+        statements[4],
+        Stmt::Expression {
+            expression: Expr::Increment { prefix: true, .. }
+        }
+    ));
+    assert!(matches!(
+        statements[5],
+        Stmt::Expression {
+            expression: Expr::Increment { prefix: false, .. }
+        }
+    ));
+
+    // Each of these reports "Invalid assignment target.".
+    let is_rejected = |source: &str| {
+        let tokens = Scanner::new(source.into()).scan_tokens().unwrap();
+        Parser::new(tokens).expression().is_err()
+    };
+    assert!(is_rejected("1 = 2"));
+    assert!(is_rejected("f() += 1"));
+    assert!(is_rejected("++1"));
+    assert!(is_rejected("(a + b)--"));
+}
+
+#[test]
+fn test_match_without_default_arm_is_not_exhaustive() {
+    use crate::lox_interpreter::scanner::Scanner;
+
+    // The parser warns about the matches this returns false for.
+    let is_exhaustive = |source: &str| {
+        let tokens = Scanner::new(source.into()).scan_tokens().unwrap();
+        match Parser::new(tokens).parse().unwrap().pop() {
+            Some(Stmt::Match { arms, .. }) => Parser::is_exhaustive(&arms),
+            other => panic!("Expected a match statement, got {:?}", other),
+        }
+    };
+
+    assert!(!is_exhaustive(
+        "match (x) { case 1 => nil; case 2..5 => nil; }"
+    ));
+    assert!(!is_exhaustive("match (x) { case n if n > 0 => nil; }"));
+    assert!(!is_exhaustive("match (x) { case true => nil; }"));
+    assert!(is_exhaustive("match (x) { case 1 => nil; case _ => nil; }"));
+    assert!(is_exhaustive("match (x) { case n => nil; }"));
+    assert!(is_exhaustive(
+        "match (x) { case true => nil; case false => nil; }"
+    ));
+}
@@ -0,0 +1,510 @@
+use std::collections::HashMap;
+
+use super::{
+    ast_tools::{
+        expr, stmt, Argument, BindingPattern, Expr, MatchArm, Parameter, Pattern, Stmt,
+        TypeAnnotation,
+    },
+    error::{report_parse_error, LoxError},
+    token::{Literal, Token},
+};
+
+// Walks the tree once before it gets run and catches the mistakes we can spot without running it,
+// right now that's assigning to a constant.
+pub struct Resolver {
+    // Mirrors the environments the interpreter will create, innermost last. The value says whether
+    // the name was declared with const.
+    scopes: Vec<HashMap<String, bool>>,
+    had_error: bool,
+}
+
+impl Default for Resolver {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Resolver {
+    pub fn new() -> Self {
+        Resolver {
+            // The global scope.
+            scopes: vec![HashMap::new()],
+            had_error: false,
+        }
+    }
+
+    pub fn resolve(&mut self, statements: &[Stmt]) -> Result<(), LoxError> {
+        for statement in statements {
+            self.resolve_stmt(statement)?;
+        }
+
+        if self.had_error {
+            return Err(LoxError::Resolve);
+        }
+        Ok(())
+    }
+
+    fn resolve_stmt(&mut self, statement: &Stmt) -> Result<(), LoxError> {
+        statement.accept(self)
+    }
+
+    fn resolve_expr(&mut self, expression: &Expr) -> Result<(), LoxError> {
+        expression.accept(self)
+    }
+
+    fn error(&mut self, token: &Token, message: &str) {
+        report_parse_error(token, message);
+        self.had_error = true;
+    }
+
+    fn begin_scope(&mut self) {
+        self.scopes.push(HashMap::new());
+    }
+
+    fn end_scope(&mut self) {
+        self.scopes.pop();
+    }
+
+    fn declare(&mut self, name: &Token, is_constant: bool) {
+        let redeclares_constant = self
+            .scopes
+            .last()
+            .and_then(|scope| scope.get(&name.lexeme))
+            .copied()
+            .unwrap_or(false);
+        if redeclares_constant {
+            self.error(
+                name,
+                &format!("Cannot redeclare constant '{}'.", name.lexeme),
+            );
+            return;
+        }
+
+        if let Some(scope) = self.scopes.last_mut() {
+            scope.insert(name.lexeme.clone(), is_constant);
+        }
+    }
+
+    // Names we never saw declared are globals defined by the interpreter itself, the interpreter
+    // checks the constant ones among those when it runs.
+    fn is_constant(&self, name: &Token) -> bool {
+        self.scopes
+            .iter()
+            .rev()
+            .find_map(|scope| scope.get(&name.lexeme))
+            .copied()
+            .unwrap_or(false)
+    }
+
+    fn check_assignment(&mut self, name: &Token) {
+        if self.is_constant(name) {
+            self.error(
+                name,
+                &format!("Cannot assign to constant '{}'.", name.lexeme),
+            );
+        }
+    }
+
+    // Compound assignment and ++/-- can target either a variable or an index, only the former
+    // could be a constant.
+    fn check_assignment_target(&mut self, target: &Expr) {
+        if let Expr::Variable { name } = target {
+            self.check_assignment(name);
+        }
+    }
+
+    fn resolve_scoped(&mut self, names: &[&Token], body: &Stmt) -> Result<(), LoxError> {
+        self.begin_scope();
+        for name in names {
+            self.declare(name, false);
+        }
+        let result = self.resolve_stmt(body);
+        self.end_scope();
+        result
+    }
+
+    fn resolve_arm(&mut self, arm: &MatchArm) -> Result<(), LoxError> {
+        // Only the pattern that matched gets bound at runtime, but whichever one it was, it shadows
+        // anything outside of the arm.
+        let bindings: Vec<&Token> = arm
+            .patterns
+            .iter()
+            .filter_map(|pattern| match pattern {
+                Pattern::Binding(name) => Some(name),
+                _ => None,
+            })
+            .collect();
+
+        self.begin_scope();
+        for name in bindings {
+            self.declare(name, false);
+        }
+        let result = match &arm.guard {
+            Some(guard) => self.resolve_expr(guard),
+            None => Ok(()),
+        }
+        .and_then(|_| self.resolve_stmt(&arm.body));
+        self.end_scope();
+        result
+    }
+}
+
+impl expr::Visitor<()> for Resolver {
+    fn visit_binary_expr(
+        &mut self,
+        left: &Expr,
+        _operator: &Token,
+        right: &Expr,
+    ) -> Result<(), LoxError> {
+        self.resolve_expr(left)?;
+        self.resolve_expr(right)
+    }
+
+    fn visit_grouping_expr(&mut self, expression: &Expr) -> Result<(), LoxError> {
+        self.resolve_expr(expression)
+    }
+
+    fn visit_literal_expr(&mut self, _value: &Literal) -> Result<(), LoxError> {
+        Ok(())
+    }
+
+    fn visit_unary_expr(&mut self, _operator: &Token, right: &Expr) -> Result<(), LoxError> {
+        self.resolve_expr(right)
+    }
+
+    fn visit_variable_expr(&mut self, _name: &Token) -> Result<(), LoxError> {
+        Ok(())
+    }
+
+    fn visit_assignment_expr(&mut self, name: &Token, value: &Expr) -> Result<(), LoxError> {
+        self.resolve_expr(value)?;
+        self.check_assignment(name);
+        Ok(())
+    }
+
+    fn visit_logical_expr(
+        &mut self,
+        left: &Expr,
+        _operator: &Token,
+        right: &Expr,
+    ) -> Result<(), LoxError> {
+        self.resolve_expr(left)?;
+        self.resolve_expr(right)
+    }
+
+    fn visit_call_expr(
+        &mut self,
+        callee: &Expr,
+        _pren: &Token,
+        arguments: &Vec<Argument>,
+    ) -> Result<(), LoxError> {
+        self.resolve_expr(callee)?;
+        for argument in arguments {
+            self.resolve_expr(&argument.value)?;
+        }
+        Ok(())
+    }
+
+    fn visit_compound_assign_expr(
+        &mut self,
+        target: &Expr,
+        _operator: &Token,
+        value: &Expr,
+    ) -> Result<(), LoxError> {
+        self.resolve_expr(target)?;
+        self.resolve_expr(value)?;
+        self.check_assignment_target(target);
+        Ok(())
+    }
+
+    fn visit_increment_expr(
+        &mut self,
+        target: &Expr,
+        _operator: &Token,
+        _prefix: bool,
+    ) -> Result<(), LoxError> {
+        self.resolve_expr(target)?;
+        self.check_assignment_target(target);
+        Ok(())
+    }
+
+    fn visit_conditional_expr(
+        &mut self,
+        condition: &Expr,
+        then_branch: &Expr,
+        else_branch: &Expr,
+    ) -> Result<(), LoxError> {
+        self.resolve_expr(condition)?;
+        self.resolve_expr(then_branch)?;
+        self.resolve_expr(else_branch)
+    }
+
+    fn visit_get_expr(
+        &mut self,
+        object: &Expr,
+        _name: &Token,
+        _optional: bool,
+    ) -> Result<(), LoxError> {
+        self.resolve_expr(object)
+    }
+
+    fn visit_list_expr(&mut self, elements: &Vec<Expr>) -> Result<(), LoxError> {
+        for element in elements {
+            self.resolve_expr(element)?;
+        }
+        Ok(())
+    }
+
+    fn visit_index_expr(
+        &mut self,
+        object: &Expr,
+        _bracket: &Token,
+        index: &Expr,
+    ) -> Result<(), LoxError> {
+        self.resolve_expr(object)?;
+        self.resolve_expr(index)
+    }
+
+    fn visit_set_index_expr(
+        &mut self,
+        object: &Expr,
+        _bracket: &Token,
+        index: &Expr,
+        value: &Expr,
+    ) -> Result<(), LoxError> {
+        self.resolve_expr(object)?;
+        self.resolve_expr(index)?;
+        self.resolve_expr(value)
+    }
+
+    fn visit_map_expr(
+        &mut self,
+        _brace: &Token,
+        entries: &Vec<(Token, Expr)>,
+    ) -> Result<(), LoxError> {
+        for (_, value) in entries {
+            self.resolve_expr(value)?;
+        }
+        Ok(())
+    }
+
+    fn visit_destructure_assign_expr(
+        &mut self,
+        pattern: &BindingPattern,
+        _equals: &Token,
+        value: &Expr,
+    ) -> Result<(), LoxError> {
+        self.resolve_expr(value)?;
+        for name in pattern.names() {
+            self.check_assignment(name);
+        }
+        Ok(())
+    }
+}
+
+impl stmt::Visitor<()> for Resolver {
+    fn visit_block_stmt(&mut self, statements: &Vec<Stmt>) -> Result<(), LoxError> {
+        self.begin_scope();
+        let result = statements
+            .iter()
+            .try_for_each(|statement| self.resolve_stmt(statement));
+        self.end_scope();
+        result
+    }
+
+    fn visit_expression_stmt(&mut self, expression: &Expr) -> Result<(), LoxError> {
+        self.resolve_expr(expression)
+    }
+
+    fn visit_print_stmt(&mut self, expression: &Expr) -> Result<(), LoxError> {
+        self.resolve_expr(expression)
+    }
+
+    fn visit_var_stmt(
+        &mut self,
+        target: &BindingPattern,
+        _annotation: &Option<TypeAnnotation>,
+        initializer: &Option<Expr>,
+    ) -> Result<(), LoxError> {
+        if let Some(initializer) = initializer {
+            self.resolve_expr(initializer)?;
+        }
+        for name in target.names() {
+            self.declare(name, false);
+        }
+        Ok(())
+    }
+
+    fn visit_const_stmt(
+        &mut self,
+        name: &Token,
+        _annotation: &Option<TypeAnnotation>,
+        initializer: &Expr,
+    ) -> Result<(), LoxError> {
+        self.resolve_expr(initializer)?;
+        self.declare(name, true);
+        Ok(())
+    }
+
+    fn visit_if_statement(
+        &mut self,
+        condition: &Expr,
+        then_branch: &Stmt,
+        else_branch: &Option<Stmt>,
+    ) -> Result<(), LoxError> {
+        self.resolve_expr(condition)?;
+        self.resolve_stmt(then_branch)?;
+        if let Some(else_branch) = else_branch {
+            self.resolve_stmt(else_branch)?;
+        }
+        Ok(())
+    }
+
+    fn visit_while_statement(
+        &mut self,
+        condition: &Expr,
+        body: &Box<Stmt>,
+        increment: &Option<Expr>,
+        _label: &Option<Token>,
+    ) -> Result<(), LoxError> {
+        self.resolve_expr(condition)?;
+        self.resolve_stmt(body)?;
+        if let Some(increment) = increment {
+            self.resolve_expr(increment)?;
+        }
+        Ok(())
+    }
+
+    fn visit_for_in_stmt(
+        &mut self,
+        variable: &Token,
+        iterable: &Expr,
+        body: &Stmt,
+        _label: &Option<Token>,
+    ) -> Result<(), LoxError> {
+        self.resolve_expr(iterable)?;
+        self.resolve_scoped(&[variable], body)
+    }
+
+    fn visit_do_while_stmt(
+        &mut self,
+        body: &Stmt,
+        condition: &Expr,
+        _label: &Option<Token>,
+    ) -> Result<(), LoxError> {
+        self.resolve_stmt(body)?;
+        self.resolve_expr(condition)
+    }
+
+    fn visit_loop_stmt(&mut self, body: &Stmt, _label: &Option<Token>) -> Result<(), LoxError> {
+        self.resolve_stmt(body)
+    }
+
+    fn visit_match_stmt(
+        &mut self,
+        _keyword: &Token,
+        subject: &Expr,
+        arms: &Vec<MatchArm>,
+    ) -> Result<(), LoxError> {
+        self.resolve_expr(subject)?;
+        for arm in arms {
+            self.resolve_arm(arm)?;
+        }
+        Ok(())
+    }
+
+    fn visit_break_stmt(
+        &mut self,
+        _keyword: &Token,
+        _label: &Option<Token>,
+    ) -> Result<(), LoxError> {
+        Ok(())
+    }
+
+    fn visit_continue_stmt(
+        &mut self,
+        _keyword: &Token,
+        _label: &Option<Token>,
+    ) -> Result<(), LoxError> {
+        Ok(())
+    }
+
+    fn visit_function_stmt(
+        &mut self,
+        name: &Token,
+        paramaters: &Vec<Parameter>,
+        _return_type: &Option<TypeAnnotation>,
+        body: &Vec<Stmt>,
+    ) -> Result<(), LoxError> {
+        self.declare(name, false);
+
+        // Parameters and the body share the same environment when the function gets called,
+        // defaults get evaluated in there too.
+        self.begin_scope();
+        let result = paramaters
+            .iter()
+            .try_for_each(|param| {
+                if let Some(default) = &param.default {
+                    self.resolve_expr(default)?;
+                }
+                for name in param.target.names() {
+                    self.declare(name, false);
+                }
+                Ok(())
+            })
+            .and_then(|_| {
+                body.iter()
+                    .try_for_each(|statement| self.resolve_stmt(statement))
+            });
+        self.end_scope();
+        result
+    }
+
+    fn visit_return_stmt(
+        &mut self,
+        _keyword: &Token,
+        value: &Option<Expr>,
+    ) -> Result<(), LoxError> {
+        if let Some(value) = value {
+            self.resolve_expr(value)?;
+        }
+        Ok(())
+    }
+}
+
+#[test]
+fn test_assigning_to_constant_is_rejected() {
+    use crate::lox_interpreter::{parser::Parser, scanner::Scanner};
+
+    let resolve = |source: &str| {
+        let tokens = Scanner::new(source.into()).scan_tokens().unwrap();
+        let statements = Parser::new(tokens).parse().unwrap();
+        Resolver::new().resolve(&statements)
+    };
+
+    assert!(resolve("const x = 1; print x + 1;").is_ok());
+    assert!(resolve("const x = 1; { var x = 2; x = 3; }").is_ok());
+    assert!(resolve("const x = 1; fun f(x) { x += 1; }").is_ok());
+    assert!(resolve("const x = 1; x = 2;").is_err());
+    assert!(resolve("const x = 1; { x++; }").is_err());
+    assert!(resolve("const x = 1; fun f() { x -= 1; }").is_err());
+}
+
+#[test]
+fn test_redeclaring_constant_is_rejected() {
+    use crate::lox_interpreter::{parser::Parser, scanner::Scanner};
+
+    let resolve = |source: &str| {
+        let tokens = Scanner::new(source.into()).scan_tokens().unwrap();
+        let statements = Parser::new(tokens).parse().unwrap();
+        Resolver::new().resolve(&statements)
+    };
+
+    assert!(resolve("var x = 1; var x = 2;").is_ok());
+    assert!(resolve("const x = 1; { var x = 2; fun x() {} }").is_ok());
+    assert!(resolve("const PORT = 80; var PORT = 8080;").is_err());
+    assert!(resolve("const PORT = 80; fun PORT() {}").is_err());
+    assert!(resolve("{ const PORT = 80; var PORT = 8080; }").is_err());
+    assert!(resolve("{ const PORT = 80; fun PORT() {} }").is_err());
+    assert!(resolve("const PORT = 80; const PORT = 8080;").is_err());
+}
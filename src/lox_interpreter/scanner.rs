@@ -3,10 +3,6 @@ use super::{
     token::{self, Literal, Token, TokenType},
 };
 
-// TODO: Visit this later and cleanup if necessary.
-#[allow(dead_code)]
-const VARIABLE_NAME_REGEX: &str = r"[a-zA-Z_][a-zA-Z_0_9]";
-
 pub struct Scanner {
     // Source file.
     source: Vec<u8>,
@@ -20,6 +16,8 @@ pub struct Scanner {
     column: usize,
     // Line of the file the cursor is at.
     line: usize,
+    // Set when a literal couldn't be turned into a token, the tokens are no use to the parser then.
+    had_error: bool,
 }
 
 impl Scanner {
@@ -31,15 +29,30 @@ impl Scanner {
             current: 0,
             column: 1,
             line: 1,
+            had_error: false,
         }
     }
 
     pub fn scan_tokens(&mut self) -> Result<Vec<Token>, LoxError> {
+        // NOTE: A `#!` line only means something at the very start, so executable scripts can name
+        // their interpreter. The newline is left for the loop so line numbers stay right.
+        if self.source.starts_with(b"#!") {
+            while self.peek() != '\n' && !self.is_at_end() {
+                self.advance();
+            }
+        }
+
         while !self.is_at_end() {
             self.start = self.current;
             self.scan_token()?;
         }
 
+        // NOTE: Stopping here means the parser doesn't pile its own errors on top of the token we
+        // had to leave out.
+        if self.had_error {
+            return Err(LoxError::Parse);
+        }
+
         self.tokens.push(Token::new(
             TokenType::EOF,
             "".to_string(),
@@ -63,12 +76,70 @@ impl Scanner {
             ')' => self.add_token(TokenType::RIGHT_PAREN, col),
             '{' => self.add_token(TokenType::LEFT_BRACE, col),
             '}' => self.add_token(TokenType::RIGHT_BRACE, col),
+            '[' => self.add_token(TokenType::LEFT_BRACKET, col),
+            ']' => self.add_token(TokenType::RIGHT_BRACKET, col),
             ',' => self.add_token(TokenType::COMMA, col),
-            '.' => self.add_token(TokenType::DOT, col),
-            '-' => self.add_token(TokenType::MINUS, col),
-            '+' => self.add_token(TokenType::PLUS, col),
+            '.' => {
+                let token_type = if self.match_next('.') {
+                    if self.match_next('=') {
+                        TokenType::DOT_DOT_EQUAL
+                    } else if self.match_next('.') {
+                        TokenType::DOT_DOT_DOT
+                    } else {
+                        TokenType::DOT_DOT
+                    }
+                } else {
+                    TokenType::DOT
+                };
+                self.add_token(token_type, col)
+            }
+            '-' => {
+                let token_type = if self.match_next('=') {
+                    TokenType::MINUS_EQUAL
+                } else if self.match_next('-') {
+                    TokenType::MINUS_MINUS
+                } else {
+                    TokenType::MINUS
+                };
+                self.add_token(token_type, col)
+            }
+            '+' => {
+                let token_type = if self.match_next('=') {
+                    TokenType::PLUS_EQUAL
+                } else if self.match_next('+') {
+                    TokenType::PLUS_PLUS
+                } else {
+                    TokenType::PLUS
+                };
+                self.add_token(token_type, col)
+            }
             ';' => self.add_token(TokenType::SEMICOLON, col),
-            '*' => self.add_token(TokenType::STAR, col),
+            ':' => self.add_token(TokenType::COLON, col),
+            '?' => {
+                let token_type = if self.match_next('?') {
+                    TokenType::QUESTION_QUESTION
+                } else if self.match_next('.') {
+                    TokenType::QUESTION_DOT
+                } else {
+                    TokenType::QUESTION
+                };
+                self.add_token(token_type, col)
+            }
+            '%' => self.add_token(TokenType::PERCENT, col),
+            '&' => self.add_token(TokenType::AMPERSAND, col),
+            '|' => self.add_token(TokenType::PIPE, col),
+            '^' => self.add_token(TokenType::CARET, col),
+            '*' => {
+                let token_type = if self.match_next('*') {
+                    TokenType::STAR_STAR
+                } else if self.match_next('=') {
+                    TokenType::STAR_EQUAL
+                } else {
+                    TokenType::STAR
+                };
+                self.add_token(token_type, col)
+            }
+            '~' if self.match_next('/') => self.add_token(TokenType::TILDE_SLASH, col),
             '!' => {
                 let token_type = if self.match_next('=') {
                     TokenType::BANG_EQUAL
@@ -80,6 +151,8 @@ impl Scanner {
             '=' => {
                 let token_type = if self.match_next('=') {
                     TokenType::EQUAL_EQUAL
+                } else if self.match_next('>') {
+                    TokenType::FAT_ARROW
                 } else {
                     TokenType::EQUAL
                 };
@@ -88,6 +161,8 @@ impl Scanner {
             '<' => {
                 let token_type = if self.match_next('=') {
                     TokenType::LESS_EQUAL
+                } else if self.match_next('<') {
+                    TokenType::LESS_LESS
                 } else {
                     TokenType::LESS
                 };
@@ -96,6 +171,8 @@ impl Scanner {
             '>' => {
                 let token_type = if self.match_next('=') {
                     TokenType::GREATER_EQUAL
+                } else if self.match_next('>') {
+                    TokenType::GREATER_GREATER
                 } else {
                     TokenType::GREATER
                 };
@@ -107,6 +184,10 @@ impl Scanner {
                         self.advance();
                     }
                     Ok(())
+                } else if self.match_next('*') {
+                    self.block_comment(col)
+                } else if self.match_next('=') {
+                    self.add_token(TokenType::SLASH_EQUAL, col)
                 } else {
                     self.add_token(TokenType::SLASH, col)
                 }
@@ -184,6 +265,39 @@ impl Scanner {
         }
     }
 
+    // Skips a `/* ... */` comment, the opening `/*` has already been consumed. Comments nest, so
+    // commenting out code that has a block comment in it does what you'd expect.
+    fn block_comment(&mut self, column: usize) -> Result<(), LoxError> {
+        let start_line = self.line;
+        let mut depth = 1;
+
+        while depth > 0 {
+            if self.is_at_end() {
+                report(
+                    column,
+                    start_line,
+                    "",
+                    &format!("Unterminated block comment at line: {}", start_line),
+                );
+                return Ok(());
+            }
+
+            if self.peek() == '/' && self.peek_next() == '*' {
+                self.advance();
+                self.advance();
+                depth += 1;
+            } else if self.peek() == '*' && self.peek_next() == '/' {
+                self.advance();
+                self.advance();
+                depth -= 1;
+            } else if self.advance() == '\n' {
+                self.line += 1;
+                self.column = 1;
+            }
+        }
+        Ok(())
+    }
+
     fn read_string(&mut self, column: usize) -> Result<(), LoxError> {
         while self.peek() != '"' && !self.is_at_end() {
             if self.peek() == '\n' {
@@ -214,30 +328,140 @@ impl Scanner {
     }
 
     fn read_number(&mut self, column: usize) -> Result<(), LoxError> {
-        while self.is_digit(self.peek()) {
-            self.advance();
+        // Prefixed integers, i.e. 0xFF, 0b1010 and 0o17.
+        if self.source[self.start] == b'0' {
+            let radix = match self.peek() {
+                'x' | 'X' => Some(16),
+                'b' | 'B' => Some(2),
+                'o' | 'O' => Some(8),
+                _ => None,
+            };
+
+            if let Some(radix) = radix {
+                // Consume the radix marker.
+                self.advance();
+                return self.read_radix_integer(radix, column);
+            }
         }
 
+        let mut is_float = false;
+        self.read_digits(10);
+
         if self.peek() == '.' && self.is_digit(self.peek_next()) {
             // We just consume the .
             self.advance();
+            is_float = true;
+            self.read_digits(10);
+        }
 
-            while self.is_digit(self.peek()) {
+        // Exponents, i.e. 1e9, 2.5E-3.
+        if self.peek() == 'e' || self.peek() == 'E' {
+            let next = self.peek_next();
+            let has_exponent = if next == '+' || next == '-' {
+                self.is_digit(self.peek_at(2))
+            } else {
+                self.is_digit(next)
+            };
+
+            if has_exponent {
+                // Consume the e and the optional sign.
                 self.advance();
+                if self.peek() == '+' || self.peek() == '-' {
+                    self.advance();
+                }
+                is_float = true;
+                self.read_digits(10);
+            }
+        }
+
+        let number_str = String::from_utf8(self.source[self.start..self.current].into())?;
+        let number_str = number_str.replace('_', "");
+
+        if is_float {
+            let number: f64 = number_str
+                .parse()
+                .expect("Scanned an invalid float literal.");
+            return self.add_token_with_literal(
+                TokenType::NUMBER,
+                token::Literal::Float(number),
+                column,
+            );
+        }
+
+        match number_str.parse::<i64>() {
+            Ok(number) => self.add_token_with_literal(
+                TokenType::NUMBER,
+                token::Literal::Integer(number),
+                column,
+            ),
+            Err(_) => {
+                report(
+                    column,
+                    self.line,
+                    "",
+                    &format!("Integer literal {} does not fit in 64 bits.", number_str),
+                );
+                self.had_error = true;
+                Ok(())
+            }
+        }
+    }
+
+    fn read_radix_integer(&mut self, radix: u32, column: usize) -> Result<(), LoxError> {
+        self.read_digits(radix);
+
+        // Skip the 0x, 0b or 0o prefix.
+        let digits = String::from_utf8(self.source[self.start + 2..self.current].into())?;
+        let digits = digits.replace('_', "");
+
+        if digits.is_empty() {
+            report(
+                column,
+                self.line,
+                "",
+                &format!("Expected digits after number prefix at line: {}", self.line),
+            );
+            self.had_error = true;
+            return Ok(());
+        }
+
+        match i64::from_str_radix(&digits, radix) {
+            Ok(number) => self.add_token_with_literal(
+                TokenType::NUMBER,
+                token::Literal::Integer(number),
+                column,
+            ),
+            Err(_) => {
+                report(
+                    column,
+                    self.line,
+                    "",
+                    &format!("Integer literal {} does not fit in 64 bits.", digits),
+                );
+                self.had_error = true;
+                Ok(())
             }
         }
+    }
 
-        let number_bytes = &self.source[self.start..self.current];
-        let number_str = String::from_utf8(number_bytes.into()).expect("Invalid UTF-8 sequence");
-        let number: f64 = number_str.parse().unwrap();
-        self.add_token_with_literal(TokenType::NUMBER, token::Literal::Float(number), column)
+    // Consumes digits of the given radix, allowing single underscores between them, i.e. 1_000.
+    fn read_digits(&mut self, radix: u32) {
+        while self.peek().is_digit(radix)
+            || (self.peek() == '_' && self.peek_next().is_digit(radix))
+        {
+            self.advance();
+        }
     }
 
     fn peek_next(&self) -> char {
-        if self.is_at_end() {
+        self.peek_at(1)
+    }
+
+    fn peek_at(&self, offset: usize) -> char {
+        if self.current + offset >= self.source.len() {
             '\0'
         } else {
-            self.source[self.current + 1] as char
+            self.source[self.current + offset] as char
         }
     }
 
@@ -264,3 +488,50 @@ impl Scanner {
         Ok(())
     }
 }
+
+#[test]
+fn test_number_literals() {
+    let mut scanner = Scanner::new("0xFF 0b1010 0o17 1_000_000 1.5 1e-9 2.5E3".into());
+    let literals: Vec<String> = scanner
+        .scan_tokens()
+        .unwrap()
+        .iter()
+        .filter(|token| token.token_type == TokenType::NUMBER)
+        .map(|token| format!("{:?}", token.literal))
+        .collect();
+
+    assert_eq!(
+        literals,
+        vec![
+            "Integer(255)",
+            "Integer(10)",
+            "Integer(15)",
+            "Integer(1000000)",
+            "Float(1.5)",
+            "Float(1e-9)",
+            "Float(2500.0)",
+        ]
+    );
+
+    // Literals that don't make a token stop the scan instead of leaving a gap for the parser.
+    assert!(Scanner::new("print 9223372036854775808;".into())
+        .scan_tokens()
+        .is_err());
+    assert!(Scanner::new("print 0x1_0000_0000_0000_0000;".into())
+        .scan_tokens()
+        .is_err());
+    assert!(Scanner::new("print 9223372036854775807;".into())
+        .scan_tokens()
+        .is_ok());
+}
+
+#[test]
+fn test_shebang_and_block_comments() {
+    let source = "#!/usr/bin/env rain\nvar /* one /* two */ still\n comment */ x = 1 /**/ / 2;";
+    let tokens = Scanner::new(source.into()).scan_tokens().unwrap();
+    let lexemes: Vec<&str> = tokens.iter().map(|token| token.lexeme.as_str()).collect();
+
+    assert_eq!(lexemes, vec!["var", "x", "=", "1", "/", "2", ";", ""]);
+    // Line numbers still count the shebang line and the newline inside the comment.
+    assert_eq!((tokens[0].line, tokens[1].line), (2, 3));
+}
@@ -0,0 +1,176 @@
+use std::{cell::RefCell, fs, io, io::Write, path::Path, rc::Rc};
+
+use super::{check_allowed, define_native, string_argument};
+use crate::lox_interpreter::{
+    environment::Environment,
+    error::LoxError,
+    interpreter::{Interpreter, Object},
+    token::Token,
+};
+
+pub fn define(environment: &mut Environment) -> Result<(), LoxError> {
+    define_native(environment, "read_file", 1, 1, read_file)?;
+    define_native(environment, "write_file", 2, 2, write_file)?;
+    define_native(environment, "append_file", 2, 2, append_file)?;
+    define_native(environment, "read_line", 0, 0, read_line)?;
+    define_native(environment, "file_exists", 1, 1, file_exists)?;
+    define_native(environment, "list_dir", 1, 1, list_dir)
+}
+
+// NOTE: A plain LoxError::IoError wouldn't say where in the script things went wrong, so the
+// io::Error goes along with the call's token instead.
+fn io_error<T>(paren: &Token, action: &str, path: &str, error: io::Error) -> Result<T, LoxError> {
+    Err(LoxError::RuntimeIo {
+        token: paren.clone(),
+        message: format!("Could not {} '{}': {}.", action, path, error),
+        source: error,
+    })
+}
+
+fn read_file(
+    interpreter: &mut Interpreter,
+    paren: &Token,
+    args: &[Object],
+) -> Result<Object, LoxError> {
+    check_allowed(interpreter, paren, "read_file")?;
+    let path = string_argument(paren, args, 0)?;
+
+    match fs::read_to_string(path) {
+        Ok(contents) => Ok(Object::String(contents)),
+        Err(error) => io_error(paren, "read", path, error),
+    }
+}
+
+// Replaces whatever the file had before, creating it if needed.
+fn write_file(
+    interpreter: &mut Interpreter,
+    paren: &Token,
+    args: &[Object],
+) -> Result<Object, LoxError> {
+    check_allowed(interpreter, paren, "write_file")?;
+    let path = string_argument(paren, args, 0)?;
+    let text = string_argument(paren, args, 1)?;
+
+    match fs::write(path, text) {
+        Ok(()) => Ok(Object::NONE),
+        Err(error) => io_error(paren, "write", path, error),
+    }
+}
+
+fn append_file(
+    interpreter: &mut Interpreter,
+    paren: &Token,
+    args: &[Object],
+) -> Result<Object, LoxError> {
+    check_allowed(interpreter, paren, "append_file")?;
+    let path = string_argument(paren, args, 0)?;
+    let text = string_argument(paren, args, 1)?;
+
+    let appended = fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(path)
+        .and_then(|mut file| file.write_all(text.as_bytes()));
+    match appended {
+        Ok(()) => Ok(Object::NONE),
+        Err(error) => io_error(paren, "append to", path, error),
+    }
+}
+
+// A line from stdin without its line ending, nil once there's nothing left to read.
+fn read_line(
+    interpreter: &mut Interpreter,
+    paren: &Token,
+    _args: &[Object],
+) -> Result<Object, LoxError> {
+    check_allowed(interpreter, paren, "read_line")?;
+
+    let mut line = String::new();
+    match io::stdin().read_line(&mut line) {
+        Ok(0) => Ok(Object::NONE),
+        Ok(_) => {
+            let trimmed = line.strip_suffix('\n').unwrap_or(&line);
+            let trimmed = trimmed.strip_suffix('\r').unwrap_or(trimmed);
+            Ok(Object::String(trimmed.to_string()))
+        }
+        Err(error) => io_error(paren, "read from", "stdin", error),
+    }
+}
+
+fn file_exists(
+    interpreter: &mut Interpreter,
+    paren: &Token,
+    args: &[Object],
+) -> Result<Object, LoxError> {
+    check_allowed(interpreter, paren, "file_exists")?;
+    let path = string_argument(paren, args, 0)?;
+    Ok(Object::Boolean(Path::new(path).exists()))
+}
+
+// The names of the entries in a directory, sorted so scripts get the same order on every system.
+fn list_dir(
+    interpreter: &mut Interpreter,
+    paren: &Token,
+    args: &[Object],
+) -> Result<Object, LoxError> {
+    check_allowed(interpreter, paren, "list_dir")?;
+    let path = string_argument(paren, args, 0)?;
+
+    let entries = match fs::read_dir(path) {
+        Ok(entries) => entries,
+        Err(error) => return io_error(paren, "list", path, error),
+    };
+    let mut names = Vec::new();
+    for entry in entries {
+        match entry {
+            Ok(entry) => names.push(entry.file_name().to_string_lossy().into_owned()),
+            Err(error) => return io_error(paren, "list", path, error),
+        }
+    }
+    names.sort();
+
+    Ok(Object::List(Rc::new(RefCell::new(
+        names.into_iter().map(Object::String).collect(),
+    ))))
+}
+
+#[test]
+fn test_file_natives_and_sandbox() {
+    use super::{call_native, call_native_in};
+
+    let dir = std::env::temp_dir().join(format!("rain-io-test-{}", std::process::id()));
+    fs::create_dir_all(&dir).unwrap();
+    let file = dir.join("notes.txt").to_string_lossy().into_owned();
+    let string = |s: &str| Object::String(s.to_string());
+
+    assert_eq!(call_native(file_exists, &[string(&file)]).unwrap(), "false");
+    call_native(write_file, &[string(&file), string("one\n")]).unwrap();
+    call_native(append_file, &[string(&file), string("two\n")]).unwrap();
+    assert_eq!(
+        call_native(read_file, &[string(&file)]).unwrap(),
+        "one\ntwo\n"
+    );
+    assert_eq!(
+        call_native(list_dir, &[string(&dir.to_string_lossy())]).unwrap(),
+        "[notes.txt]"
+    );
+
+    let missing = dir.join("missing.txt").to_string_lossy().into_owned();
+    match call_native(read_file, &[string(&missing)]) {
+        Err(LoxError::RuntimeIo { source, .. }) => {
+            assert_eq!(source.kind(), io::ErrorKind::NotFound)
+        }
+        other => panic!("Expected an io error, got {:?}", other),
+    }
+
+    let mut sandboxed = Interpreter::new();
+    sandboxed.set_sandboxed(true);
+    match call_native_in(&mut sandboxed, read_file, &[string(&file)]) {
+        Err(LoxError::Runtime { message, .. }) => {
+            assert_eq!(message, "'read_file' is not available in a sandbox.")
+        }
+        other => panic!("Expected a runtime error, got {:?}", other),
+    }
+
+    fs::remove_dir_all(&dir).unwrap();
+}
@@ -0,0 +1,95 @@
+use core::fmt;
+
+use super::{define_native, integer_argument, runtime_error};
+use crate::lox_interpreter::{
+    environment::Environment,
+    error::LoxError,
+    interpreter::{Interpreter, Object},
+    token::Token,
+};
+
+pub fn define(environment: &mut Environment) -> Result<(), LoxError> {
+    define_native(environment, "range", 1, 3, range)
+}
+
+// Lazy sequence of integers, so `for (i in range(0, 1000000000))` doesn't allocate anything.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Range {
+    pub start: i64,
+    pub end: i64,
+    pub step: i64,
+}
+
+impl Iterator for Range {
+    type Item = i64;
+
+    fn next(&mut self) -> Option<i64> {
+        let has_next =
+            (self.step > 0 && self.start < self.end) || (self.step < 0 && self.start > self.end);
+        if !has_next {
+            return None;
+        }
+
+        let current = self.start;
+        // NOTE: If stepping overflows there is nothing left to yield anyway.
+        self.start = current.checked_add(self.step).unwrap_or(self.end);
+        Some(current)
+    }
+}
+
+impl fmt::Display for Range {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "range({}, {}, {})", self.start, self.end, self.step)
+    }
+}
+
+// range(end), range(start, end) or range(start, end, step), end is never included.
+fn range(
+    _interpreter: &mut Interpreter,
+    paren: &Token,
+    args: &[Object],
+) -> Result<Object, LoxError> {
+    let (start, end) = if args.len() == 1 {
+        (0, integer_argument(paren, args, 0)?)
+    } else {
+        (
+            integer_argument(paren, args, 0)?,
+            integer_argument(paren, args, 1)?,
+        )
+    };
+    let step = if args.len() == 3 {
+        integer_argument(paren, args, 2)?
+    } else {
+        1
+    };
+
+    if step == 0 {
+        return runtime_error(paren, "Range step cannot be zero.".to_string());
+    }
+
+    Ok(Object::Range(Range { start, end, step }))
+}
+
+#[test]
+fn test_range_steps() {
+    let forward = Range {
+        start: 0,
+        end: 10,
+        step: 3,
+    };
+    assert_eq!(forward.collect::<Vec<i64>>(), vec![0, 3, 6, 9]);
+
+    let backward = Range {
+        start: 5,
+        end: 0,
+        step: -2,
+    };
+    assert_eq!(backward.collect::<Vec<i64>>(), vec![5, 3, 1]);
+
+    let overflowing = Range {
+        start: i64::MAX - 1,
+        end: i64::MAX,
+        step: 5,
+    };
+    assert_eq!(overflowing.collect::<Vec<i64>>(), vec![i64::MAX - 1]);
+}
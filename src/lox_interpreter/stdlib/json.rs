@@ -0,0 +1,230 @@
+use std::{cell::RefCell, collections::BTreeMap, rc::Rc};
+
+use super::{define_native, integer_argument, runtime_error, string_argument};
+use crate::lox_interpreter::{
+    environment::Environment,
+    error::LoxError,
+    interpreter::{Interpreter, Object},
+    json::Json,
+    token::Token,
+};
+
+pub fn define(environment: &mut Environment) -> Result<(), LoxError> {
+    define_native(environment, "json_parse", 1, 1, json_parse)?;
+    define_native(environment, "json_stringify", 1, 2, json_stringify)
+}
+
+fn json_parse(
+    _interpreter: &mut Interpreter,
+    paren: &Token,
+    args: &[Object],
+) -> Result<Object, LoxError> {
+    let text = string_argument(paren, args, 0)?;
+    match Json::parse(text) {
+        Ok(json) => Ok(to_object(json)),
+        Err(LoxError::Error(message)) => runtime_error(paren, message),
+        Err(other) => Err(other),
+    }
+}
+
+// json_stringify(value) gives compact text, json_stringify(value, indent) spreads it over lines.
+fn json_stringify(
+    _interpreter: &mut Interpreter,
+    paren: &Token,
+    args: &[Object],
+) -> Result<Object, LoxError> {
+    let indent = if args.len() == 2 {
+        integer_argument(paren, args, 1)?
+    } else {
+        0
+    };
+    if indent < 0 {
+        return runtime_error(paren, "Indent cannot be negative.".to_string());
+    }
+
+    let json = to_json(paren, &args[0], &mut Vec::new())?;
+    Ok(Object::String(json.pretty(indent as usize)))
+}
+
+// NOTE: When a key shows up more than once in an object the last one wins, like everywhere else.
+fn to_object(json: Json) -> Object {
+    match json {
+        Json::Null => Object::NONE,
+        Json::Bool(bool) => Object::Boolean(bool),
+        Json::Integer(integer) => Object::Integer(integer),
+        Json::Float(float) => Object::Number(float),
+        Json::String(string) => Object::String(string),
+        Json::Array(elements) => Object::List(Rc::new(RefCell::new(
+            elements.into_iter().map(to_object).collect(),
+        ))),
+        Json::Object(fields) => Object::Map(Rc::new(RefCell::new(
+            fields
+                .into_iter()
+                .map(|(key, value)| (key, to_object(value)))
+                .collect::<BTreeMap<String, Object>>(),
+        ))),
+    }
+}
+
+// `parents` holds the lists and maps we're currently inside of. Running into one of them again
+// means the structure contains itself and would never finish printing. The same list showing up
+// twice side by side is fine.
+fn to_json(paren: &Token, value: &Object, parents: &mut Vec<*const ()>) -> Result<Json, LoxError> {
+    let json = match value {
+        Object::NONE => Json::Null,
+        Object::Boolean(bool) => Json::Bool(*bool),
+        Object::Integer(integer) => Json::Integer(*integer),
+        Object::Number(number) if number.is_finite() => Json::Float(*number),
+        Object::Number(number) => {
+            return runtime_error(paren, format!("Cannot convert {} to JSON.", number))
+        }
+        Object::String(string) => Json::String(string.clone()),
+        Object::List(list) => {
+            let pointer = Rc::as_ptr(list) as *const ();
+            enter(paren, parents, pointer)?;
+            let elements = list
+                .borrow()
+                .iter()
+                .map(|element| to_json(paren, element, parents))
+                .collect::<Result<Vec<Json>, LoxError>>()?;
+            parents.pop();
+            Json::Array(elements)
+        }
+        Object::Map(map) => {
+            let pointer = Rc::as_ptr(map) as *const ();
+            enter(paren, parents, pointer)?;
+            let fields = map
+                .borrow()
+                .iter()
+                .map(|(key, value)| Ok((key.clone(), to_json(paren, value, parents)?)))
+                .collect::<Result<Vec<(String, Json)>, LoxError>>()?;
+            parents.pop();
+            Json::Object(fields)
+        }
+        Object::Callable(_) | Object::NativeFunction(_) => {
+            return runtime_error(paren, "Cannot convert a function to JSON.".to_string())
+        }
+        Object::Range(_) => {
+            return runtime_error(paren, "Cannot convert a range to JSON.".to_string())
+        }
+    };
+    Ok(json)
+}
+
+fn enter(paren: &Token, parents: &mut Vec<*const ()>, pointer: *const ()) -> Result<(), LoxError> {
+    if parents.contains(&pointer) {
+        return runtime_error(
+            paren,
+            "Cannot convert a structure that contains itself to JSON.".to_string(),
+        );
+    }
+    parents.push(pointer);
+    Ok(())
+}
+
+#[test]
+fn test_json_natives_round_trip() {
+    use super::call_native_in;
+
+    let mut interpreter = Interpreter::new();
+    let text = r#"{"name":"rain \"lox\"","tags":["a","ü"],"version":1,"ratio":0.5,"extra":null,"ok":true}"#;
+
+    let parsed = call_native_in(
+        &mut interpreter,
+        json_parse,
+        &[Object::String(text.to_string())],
+    )
+    .unwrap();
+    let compact = call_native_in(
+        &mut interpreter,
+        json_stringify,
+        std::slice::from_ref(&parsed),
+    )
+    .unwrap();
+    // Map keys come back sorted.
+    assert_eq!(
+        compact.to_string(),
+        r#"{"extra":null,"name":"rain \"lox\"","ok":true,"ratio":0.5,"tags":["a","ü"],"version":1}"#
+    );
+    let reparsed = call_native_in(&mut interpreter, json_parse, &[compact]).unwrap();
+    assert_eq!(reparsed.to_string(), parsed.to_string());
+
+    let nested = call_native_in(
+        &mut interpreter,
+        json_parse,
+        &[Object::String(r#"{"a": [1, {}], "b": []}"#.to_string())],
+    )
+    .unwrap();
+    assert_eq!(
+        call_native_in(
+            &mut interpreter,
+            json_stringify,
+            &[nested, Object::Integer(2)]
+        )
+        .unwrap()
+        .to_string(),
+        "{\n  \"a\": [\n    1,\n    {}\n  ],\n  \"b\": []\n}"
+    );
+
+    // The same list twice is fine, a list inside itself isn't.
+    let shared = Object::List(Rc::new(RefCell::new(vec![Object::Integer(1)])));
+    let twice = Object::List(Rc::new(RefCell::new(vec![shared.clone(), shared])));
+    assert!(call_native_in(&mut interpreter, json_stringify, &[twice]).is_ok());
+    let cyclic = Rc::new(RefCell::new(Vec::new()));
+    cyclic.borrow_mut().push(Object::List(Rc::clone(&cyclic)));
+    let message = |result: Result<Object, LoxError>| match result {
+        Err(LoxError::Runtime { message, .. }) => message,
+        other => panic!("Expected a runtime error, got {:?}", other),
+    };
+    assert_eq!(
+        message(call_native_in(
+            &mut interpreter,
+            json_stringify,
+            &[Object::List(Rc::clone(&cyclic))]
+        )),
+        "Cannot convert a structure that contains itself to JSON."
+    );
+    // Break the cycle so the test doesn't leak it.
+    cyclic.borrow_mut().clear();
+
+    let function = Object::NativeFunction(crate::lox_interpreter::function::NativeFunction::new(
+        "json_parse",
+        1,
+        1,
+        json_parse,
+    ));
+    assert_eq!(
+        message(call_native_in(
+            &mut interpreter,
+            json_stringify,
+            &[function]
+        )),
+        "Cannot convert a function to JSON."
+    );
+    assert!(call_native_in(
+        &mut interpreter,
+        json_parse,
+        &[Object::String("[1, 2".to_string())]
+    )
+    .is_err());
+}
+
+#[test]
+fn test_json_parse_limits_nesting() {
+    use super::call_native_in;
+
+    let mut interpreter = Interpreter::new();
+    let nested =
+        |depth: usize| Object::String(format!("{}{}", "[".repeat(depth), "]".repeat(depth)));
+
+    assert!(call_native_in(&mut interpreter, json_parse, &[nested(512)]).is_ok());
+    match call_native_in(&mut interpreter, json_parse, &[nested(10_000)]) {
+        Err(LoxError::Runtime { message, .. }) => {
+            assert_eq!(
+                message,
+                "Invalid JSON at character 512: Nesting is too deep."
+            )
+        }
+        other => panic!("Expected a runtime error, got {:?}", other),
+    }
+}
@@ -0,0 +1,213 @@
+use super::{define_native, number_argument, runtime_error, VARIADIC};
+use crate::lox_interpreter::{
+    environment::Environment,
+    error::LoxError,
+    interpreter::{Interpreter, Object},
+    token::Token,
+};
+
+pub fn define(environment: &mut Environment) -> Result<(), LoxError> {
+    environment.define_builtin("PI", Object::Number(std::f64::consts::PI))?;
+    environment.define_builtin("E", Object::Number(std::f64::consts::E))?;
+
+    define_native(environment, "abs", 1, 1, abs)?;
+    define_native(environment, "sqrt", 1, 1, sqrt)?;
+    define_native(environment, "pow", 2, 2, pow)?;
+    define_native(environment, "floor", 1, 1, floor)?;
+    define_native(environment, "ceil", 1, 1, ceil)?;
+    define_native(environment, "round", 1, 1, round)?;
+    define_native(environment, "min", 1, VARIADIC, min)?;
+    define_native(environment, "max", 1, VARIADIC, max)?;
+    define_native(environment, "sin", 1, 1, sin)?;
+    define_native(environment, "cos", 1, 1, cos)?;
+    define_native(environment, "tan", 1, 1, tan)?;
+    define_native(environment, "log", 1, 2, log)?;
+    define_native(environment, "exp", 1, 1, exp)
+}
+
+// Integers stay integers, same as `-` does.
+fn abs(_interpreter: &mut Interpreter, paren: &Token, args: &[Object]) -> Result<Object, LoxError> {
+    match &args[0] {
+        Object::Integer(integer) => match integer.checked_abs() {
+            Some(value) => Ok(Object::Integer(value)),
+            None => runtime_error(paren, "Integer overflow.".to_string()),
+        },
+        _ => Ok(Object::Number(number_argument(paren, args, 0)?.abs())),
+    }
+}
+
+fn sqrt(
+    _interpreter: &mut Interpreter,
+    paren: &Token,
+    args: &[Object],
+) -> Result<Object, LoxError> {
+    let number = number_argument(paren, args, 0)?;
+    if number < 0.0 {
+        return runtime_error(
+            paren,
+            "Cannot take the square root of a negative number.".to_string(),
+        );
+    }
+    Ok(Object::Number(number.sqrt()))
+}
+
+// Same rules as `**`, two integers give an integer as long as the exponent isn't negative.
+fn pow(_interpreter: &mut Interpreter, paren: &Token, args: &[Object]) -> Result<Object, LoxError> {
+    if let (Object::Integer(base), Object::Integer(exponent)) = (&args[0], &args[1]) {
+        if *exponent >= 0 {
+            return match u32::try_from(*exponent)
+                .ok()
+                .and_then(|exponent| base.checked_pow(exponent))
+            {
+                Some(value) => Ok(Object::Integer(value)),
+                None => runtime_error(paren, "Integer overflow.".to_string()),
+            };
+        }
+    }
+
+    let base = number_argument(paren, args, 0)?;
+    let exponent = number_argument(paren, args, 1)?;
+    Ok(Object::Number(base.powf(exponent)))
+}
+
+fn floor(
+    _interpreter: &mut Interpreter,
+    paren: &Token,
+    args: &[Object],
+) -> Result<Object, LoxError> {
+    rounded(paren, args, f64::floor)
+}
+
+fn ceil(
+    _interpreter: &mut Interpreter,
+    paren: &Token,
+    args: &[Object],
+) -> Result<Object, LoxError> {
+    rounded(paren, args, f64::ceil)
+}
+
+// Halfway cases round away from zero, `round(2.5)` is 3 and `round(-2.5)` is -3.
+fn round(
+    _interpreter: &mut Interpreter,
+    paren: &Token,
+    args: &[Object],
+) -> Result<Object, LoxError> {
+    rounded(paren, args, f64::round)
+}
+
+// floor, ceil and round all give back integers, there's nothing after the point left to keep.
+fn rounded(paren: &Token, args: &[Object], rounding: fn(f64) -> f64) -> Result<Object, LoxError> {
+    if let Object::Integer(integer) = args[0] {
+        return Ok(Object::Integer(integer));
+    }
+
+    let value = rounding(number_argument(paren, args, 0)?);
+    // NOTE: i64::MAX as f64 rounds up to 2^63, which is already out of range, hence the `<`.
+    if value.is_nan() || value < i64::MIN as f64 || value >= i64::MAX as f64 {
+        return runtime_error(paren, format!("Cannot convert {} to an integer.", value));
+    }
+    Ok(Object::Integer(value as i64))
+}
+
+fn min(_interpreter: &mut Interpreter, paren: &Token, args: &[Object]) -> Result<Object, LoxError> {
+    extreme(paren, args, |candidate, best| candidate < best)
+}
+
+fn max(_interpreter: &mut Interpreter, paren: &Token, args: &[Object]) -> Result<Object, LoxError> {
+    extreme(paren, args, |candidate, best| candidate > best)
+}
+
+// The argument that wins every comparison, returned as it was passed so integers stay integers.
+fn extreme(
+    paren: &Token,
+    args: &[Object],
+    better: fn(f64, f64) -> bool,
+) -> Result<Object, LoxError> {
+    let mut best = 0;
+    let mut best_value = number_argument(paren, args, 0)?;
+    for index in 1..args.len() {
+        let value = number_argument(paren, args, index)?;
+        if better(value, best_value) {
+            best = index;
+            best_value = value;
+        }
+    }
+    Ok(args[best].clone())
+}
+
+fn sin(_interpreter: &mut Interpreter, paren: &Token, args: &[Object]) -> Result<Object, LoxError> {
+    Ok(Object::Number(number_argument(paren, args, 0)?.sin()))
+}
+
+fn cos(_interpreter: &mut Interpreter, paren: &Token, args: &[Object]) -> Result<Object, LoxError> {
+    Ok(Object::Number(number_argument(paren, args, 0)?.cos()))
+}
+
+fn tan(_interpreter: &mut Interpreter, paren: &Token, args: &[Object]) -> Result<Object, LoxError> {
+    Ok(Object::Number(number_argument(paren, args, 0)?.tan()))
+}
+
+// log(x) is the natural logarithm, log(x, base) uses the given base.
+fn log(_interpreter: &mut Interpreter, paren: &Token, args: &[Object]) -> Result<Object, LoxError> {
+    let number = number_argument(paren, args, 0)?;
+    if number <= 0.0 {
+        return runtime_error(
+            paren,
+            "Cannot take the logarithm of a number that isn't positive.".to_string(),
+        );
+    }
+
+    if args.len() == 2 {
+        let base = number_argument(paren, args, 1)?;
+        if base <= 0.0 || base == 1.0 {
+            return runtime_error(
+                paren,
+                "Logarithm base must be positive and not 1.".to_string(),
+            );
+        }
+        return Ok(Object::Number(number.log(base)));
+    }
+    Ok(Object::Number(number.ln()))
+}
+
+fn exp(_interpreter: &mut Interpreter, paren: &Token, args: &[Object]) -> Result<Object, LoxError> {
+    Ok(Object::Number(number_argument(paren, args, 0)?.exp()))
+}
+
+#[test]
+fn test_math_natives() {
+    use super::call_native;
+
+    assert_eq!(call_native(abs, &[Object::Integer(-3)]).unwrap(), "3");
+    assert_eq!(
+        call_native(pow, &[Object::Integer(2), Object::Integer(10)]).unwrap(),
+        "1024"
+    );
+    assert_eq!(
+        call_native(pow, &[Object::Integer(2), Object::Integer(-1)]).unwrap(),
+        "0.5"
+    );
+    assert_eq!(call_native(round, &[Object::Number(-2.5)]).unwrap(), "-3");
+    assert_eq!(call_native(floor, &[Object::Number(-0.5)]).unwrap(), "-1");
+    assert_eq!(
+        call_native(
+            max,
+            &[Object::Integer(1), Object::Number(2.5), Object::Integer(2)]
+        )
+        .unwrap(),
+        "2.5"
+    );
+    assert_eq!(
+        call_native(log, &[Object::Integer(8), Object::Integer(2)]).unwrap(),
+        "3"
+    );
+
+    match call_native(sqrt, &[Object::String("4".to_string())]) {
+        Err(LoxError::Runtime { token, message }) => {
+            assert_eq!(token.lexeme, ")");
+            assert_eq!(message, "Argument 1 must be a number.");
+        }
+        other => panic!("Expected a runtime error, got {:?}", other),
+    }
+    assert!(call_native(ceil, &[Object::Number(f64::INFINITY)]).is_err());
+}
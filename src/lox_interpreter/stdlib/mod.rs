@@ -0,0 +1,110 @@
+// Built-in functions every script gets in its global environment. Each submodule owns a group of
+// natives and registers them through its `define` function.
+use super::{
+    environment::Environment,
+    error::LoxError,
+    function::{NativeFn, NativeFunction},
+    interpreter::{Interpreter, Object},
+    token::Token,
+};
+
+pub mod io;
+pub mod iter;
+pub mod json;
+pub mod math;
+pub mod random;
+pub mod regex;
+pub mod string;
+pub mod system;
+pub mod time;
+pub mod types;
+
+pub fn define_natives(environment: &mut Environment) -> Result<(), LoxError> {
+    iter::define(environment)?;
+    math::define(environment)?;
+    string::define(environment)?;
+    io::define(environment)?;
+    regex::define(environment)?;
+    json::define(environment)?;
+    random::define(environment)?;
+    time::define(environment)?;
+    system::define(environment)?;
+    types::define(environment)?;
+    Ok(())
+}
+
+// Max arity for natives that take any number of arguments.
+pub const VARIADIC: usize = usize::MAX;
+
+fn define_native(
+    environment: &mut Environment,
+    name: &'static str,
+    min_arity: usize,
+    max_arity: usize,
+    function: NativeFn,
+) -> Result<(), LoxError> {
+    environment.define_builtin(
+        name,
+        Object::NativeFunction(NativeFunction::new(name, min_arity, max_arity, function)),
+    )
+}
+
+// For the natives that reach outside the interpreter, none of them run when it's sandboxed.
+fn check_allowed(interpreter: &Interpreter, paren: &Token, name: &str) -> Result<(), LoxError> {
+    if interpreter.is_sandboxed() {
+        return runtime_error(paren, format!("'{}' is not available in a sandbox.", name));
+    }
+    Ok(())
+}
+
+fn runtime_error<T>(paren: &Token, message: String) -> Result<T, LoxError> {
+    Err(LoxError::Runtime {
+        token: paren.clone(),
+        message,
+    })
+}
+
+fn integer_argument(paren: &Token, args: &[Object], index: usize) -> Result<i64, LoxError> {
+    match args.get(index) {
+        Some(Object::Integer(integer)) => Ok(*integer),
+        _ => runtime_error(paren, format!("Argument {} must be an integer.", index + 1)),
+    }
+}
+
+// Integers are widened, a float is expected anyway.
+fn number_argument(paren: &Token, args: &[Object], index: usize) -> Result<f64, LoxError> {
+    match args.get(index).and_then(Object::as_float) {
+        Some(number) => Ok(number),
+        None => runtime_error(paren, format!("Argument {} must be a number.", index + 1)),
+    }
+}
+
+fn string_argument<'a>(
+    paren: &Token,
+    args: &'a [Object],
+    index: usize,
+) -> Result<&'a str, LoxError> {
+    match args.get(index) {
+        Some(Object::String(string)) => Ok(string),
+        _ => runtime_error(paren, format!("Argument {} must be a string.", index + 1)),
+    }
+}
+
+// Calls a native the way a script would, errors point at a `)` on line 1.
+#[cfg(test)]
+fn call_native_in(
+    interpreter: &mut Interpreter,
+    function: NativeFn,
+    args: &[Object],
+) -> Result<Object, LoxError> {
+    use super::token::{Literal, TokenType};
+
+    let paren = Token::new(TokenType::RIGHT_PAREN, ")".to_string(), Literal::None, 1, 1);
+    function(interpreter, &paren, args)
+}
+
+// Same as call_native_in with a fresh interpreter, for the natives that don't keep any state.
+#[cfg(test)]
+fn call_native(function: NativeFn, args: &[Object]) -> Result<String, LoxError> {
+    call_native_in(&mut Interpreter::new(), function, args).map(|value| value.to_string())
+}
@@ -0,0 +1,188 @@
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use super::{define_native, integer_argument, runtime_error};
+use crate::lox_interpreter::{
+    environment::Environment,
+    error::LoxError,
+    interpreter::{Interpreter, Object},
+    token::Token,
+};
+
+pub fn define(environment: &mut Environment) -> Result<(), LoxError> {
+    define_native(environment, "seed", 1, 1, seed)?;
+    define_native(environment, "random", 0, 0, random)?;
+    define_native(environment, "random_int", 2, 2, random_int)?;
+    define_native(environment, "shuffle", 1, 1, shuffle)?;
+    define_native(environment, "choice", 1, 1, choice)
+}
+
+// SplitMix64. Not fit for anything secret, but it's fast, any seed (even 0) gives a good sequence,
+// and the same seed always gives the same numbers on every platform.
+#[derive(Debug, Clone)]
+pub struct Rng {
+    state: u64,
+}
+
+impl Rng {
+    pub fn new(seed: u64) -> Self {
+        Rng { state: seed }
+    }
+
+    // Seeded from the clock, for runs that didn't ask for a seed.
+    pub fn from_time() -> Self {
+        let nanos = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |elapsed| elapsed.as_nanos() as u64);
+        Rng::new(nanos)
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut z = self.state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
+    }
+
+    // Uniform in [0, 1), built from the top 53 bits so every value is exactly representable.
+    pub fn next_float(&mut self) -> f64 {
+        (self.next_u64() >> 11) as f64 / (1u64 << 53) as f64
+    }
+
+    // Uniform in [0, bound). Values from the uneven tail of the u64 range are thrown away so small
+    // numbers aren't picked more often than big ones.
+    pub fn below(&mut self, bound: u64) -> u64 {
+        let zone = u64::MAX - (u64::MAX % bound);
+        loop {
+            let value = self.next_u64();
+            if value < zone {
+                return value % bound;
+            }
+        }
+    }
+}
+
+// seed(n) restarts the sequence, the same seed gives the same numbers every run.
+fn seed(interpreter: &mut Interpreter, paren: &Token, args: &[Object]) -> Result<Object, LoxError> {
+    let seed = integer_argument(paren, args, 0)?;
+    *interpreter.rng() = Rng::new(seed as u64);
+    Ok(Object::NONE)
+}
+
+// A float in [0, 1).
+fn random(
+    interpreter: &mut Interpreter,
+    _paren: &Token,
+    _args: &[Object],
+) -> Result<Object, LoxError> {
+    Ok(Object::Number(interpreter.rng().next_float()))
+}
+
+// random_int(lo, hi), both ends can come up.
+fn random_int(
+    interpreter: &mut Interpreter,
+    paren: &Token,
+    args: &[Object],
+) -> Result<Object, LoxError> {
+    let low = integer_argument(paren, args, 0)?;
+    let high = integer_argument(paren, args, 1)?;
+    if low > high {
+        return runtime_error(
+            paren,
+            format!("random_int low {} is greater than high {}.", low, high),
+        );
+    }
+
+    // NOTE: The span is computed in u64 so that i64::MIN..=i64::MAX doesn't overflow. That one
+    // span wraps around to 0, and there any u64 will do.
+    let span = (high as u64).wrapping_sub(low as u64).wrapping_add(1);
+    let offset = if span == 0 {
+        interpreter.rng().next_u64()
+    } else {
+        interpreter.rng().below(span)
+    };
+    Ok(Object::Integer((low as u64).wrapping_add(offset) as i64))
+}
+
+// Shuffles the list in place, Fisher-Yates.
+fn shuffle(
+    interpreter: &mut Interpreter,
+    paren: &Token,
+    args: &[Object],
+) -> Result<Object, LoxError> {
+    let Object::List(list) = &args[0] else {
+        return runtime_error(paren, "Argument 1 must be a list.".to_string());
+    };
+
+    let mut list = list.borrow_mut();
+    for i in (1..list.len()).rev() {
+        let j = interpreter.rng().below(i as u64 + 1) as usize;
+        list.swap(i, j);
+    }
+    Ok(Object::NONE)
+}
+
+fn choice(
+    interpreter: &mut Interpreter,
+    paren: &Token,
+    args: &[Object],
+) -> Result<Object, LoxError> {
+    let Object::List(list) = &args[0] else {
+        return runtime_error(paren, "Argument 1 must be a list.".to_string());
+    };
+
+    let list = list.borrow();
+    if list.is_empty() {
+        return runtime_error(paren, "Cannot choose from an empty list.".to_string());
+    }
+    let index = interpreter.rng().below(list.len() as u64) as usize;
+    Ok(list[index].clone())
+}
+
+#[test]
+fn test_seeded_random_is_reproducible() {
+    use super::call_native_in;
+    use std::{cell::RefCell, rc::Rc};
+
+    let run = || {
+        let mut interpreter = Interpreter::new();
+        call_native_in(&mut interpreter, seed, &[Object::Integer(42)]).unwrap();
+
+        let list = Object::List(Rc::new(RefCell::new(
+            (0..10).map(Object::Integer).collect(),
+        )));
+        call_native_in(&mut interpreter, shuffle, std::slice::from_ref(&list)).unwrap();
+        let mut drawn = vec![list.to_string()];
+        for _ in 0..20 {
+            let value = call_native_in(
+                &mut interpreter,
+                random_int,
+                &[Object::Integer(-2), Object::Integer(2)],
+            )
+            .unwrap();
+            match value {
+                Object::Integer(n) => assert!((-2..=2).contains(&n)),
+                other => panic!("Expected an integer, got {:?}", other),
+            }
+            drawn.push(value.to_string());
+        }
+        match call_native_in(&mut interpreter, random, &[]).unwrap() {
+            Object::Number(n) => assert!((0.0..1.0).contains(&n)),
+            other => panic!("Expected a number, got {:?}", other),
+        }
+        drawn.push(
+            call_native_in(&mut interpreter, choice, &[list])
+                .unwrap()
+                .to_string(),
+        );
+        drawn
+    };
+
+    assert_eq!(run(), run());
+    assert!(call_native_in(
+        &mut Interpreter::new(),
+        random_int,
+        &[Object::Integer(i64::MIN), Object::Integer(i64::MAX)]
+    )
+    .is_ok());
+}
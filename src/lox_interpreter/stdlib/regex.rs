@@ -0,0 +1,193 @@
+use std::{cell::RefCell, collections::BTreeMap, collections::HashMap, rc::Rc};
+
+use regex::Regex;
+
+use super::{define_native, runtime_error, string_argument};
+use crate::lox_interpreter::{
+    environment::Environment,
+    error::LoxError,
+    interpreter::{Interpreter, Object},
+    token::Token,
+};
+
+// Past this many patterns the cache starts over, a script building patterns in a loop shouldn't
+// be able to grow it forever.
+const MAX_CACHED_PATTERNS: usize = 64;
+
+pub fn define(environment: &mut Environment) -> Result<(), LoxError> {
+    define_native(environment, "regex_match", 2, 2, regex_match)?;
+    define_native(environment, "regex_find_all", 2, 2, regex_find_all)?;
+    define_native(environment, "regex_replace", 3, 3, regex_replace)?;
+    define_native(environment, "regex_captures", 2, 2, regex_captures)?;
+    define_native(
+        environment,
+        "regex_named_captures",
+        2,
+        2,
+        regex_named_captures,
+    )
+}
+
+// Compiled patterns by their source, so a regex used inside a loop is only compiled once.
+#[derive(Debug, Default)]
+pub struct RegexCache {
+    patterns: HashMap<String, Regex>,
+}
+
+impl RegexCache {
+    fn get(&mut self, paren: &Token, pattern: &str) -> Result<Regex, LoxError> {
+        if let Some(regex) = self.patterns.get(pattern) {
+            return Ok(regex.clone());
+        }
+
+        let regex = match Regex::new(pattern) {
+            Ok(regex) => regex,
+            Err(error) => return runtime_error(paren, format!("Invalid regex: {}", error)),
+        };
+        if self.patterns.len() >= MAX_CACHED_PATTERNS {
+            self.patterns.clear();
+        }
+        self.patterns.insert(pattern.to_string(), regex.clone());
+        Ok(regex)
+    }
+}
+
+fn compiled(
+    interpreter: &mut Interpreter,
+    paren: &Token,
+    args: &[Object],
+) -> Result<Regex, LoxError> {
+    let pattern = string_argument(paren, args, 0)?;
+    interpreter.regex_cache().get(paren, pattern)
+}
+
+fn list(values: Vec<Object>) -> Object {
+    Object::List(Rc::new(RefCell::new(values)))
+}
+
+fn optional_string(value: Option<regex::Match>) -> Object {
+    value.map_or(Object::NONE, |value| {
+        Object::String(value.as_str().to_string())
+    })
+}
+
+// Whether the pattern matches anywhere in the string, anchor it with `^...$` to match all of it.
+fn regex_match(
+    interpreter: &mut Interpreter,
+    paren: &Token,
+    args: &[Object],
+) -> Result<Object, LoxError> {
+    let regex = compiled(interpreter, paren, args)?;
+    let string = string_argument(paren, args, 1)?;
+    Ok(Object::Boolean(regex.is_match(string)))
+}
+
+fn regex_find_all(
+    interpreter: &mut Interpreter,
+    paren: &Token,
+    args: &[Object],
+) -> Result<Object, LoxError> {
+    let regex = compiled(interpreter, paren, args)?;
+    let string = string_argument(paren, args, 1)?;
+    Ok(list(
+        regex
+            .find_iter(string)
+            .map(|found| Object::String(found.as_str().to_string()))
+            .collect(),
+    ))
+}
+
+// Replaces every match. The replacement can refer to groups with `$1` or `${name}`.
+fn regex_replace(
+    interpreter: &mut Interpreter,
+    paren: &Token,
+    args: &[Object],
+) -> Result<Object, LoxError> {
+    let regex = compiled(interpreter, paren, args)?;
+    let string = string_argument(paren, args, 1)?;
+    let replacement = string_argument(paren, args, 2)?;
+    Ok(Object::String(
+        regex.replace_all(string, replacement).into_owned(),
+    ))
+}
+
+// The groups of the first match as a list, the whole match first. Groups that didn't take part in
+// the match are nil, and so is the result when nothing matched.
+fn regex_captures(
+    interpreter: &mut Interpreter,
+    paren: &Token,
+    args: &[Object],
+) -> Result<Object, LoxError> {
+    let regex = compiled(interpreter, paren, args)?;
+    let string = string_argument(paren, args, 1)?;
+    let Some(captures) = regex.captures(string) else {
+        return Ok(Object::NONE);
+    };
+    Ok(list(captures.iter().map(optional_string).collect()))
+}
+
+// Same as regex_captures, but only the named groups, as a map from name to text.
+fn regex_named_captures(
+    interpreter: &mut Interpreter,
+    paren: &Token,
+    args: &[Object],
+) -> Result<Object, LoxError> {
+    let regex = compiled(interpreter, paren, args)?;
+    let string = string_argument(paren, args, 1)?;
+    let Some(captures) = regex.captures(string) else {
+        return Ok(Object::NONE);
+    };
+
+    let groups: BTreeMap<String, Object> = regex
+        .capture_names()
+        .flatten()
+        .map(|name| (name.to_string(), optional_string(captures.name(name))))
+        .collect();
+    Ok(Object::Map(Rc::new(RefCell::new(groups))))
+}
+
+#[test]
+fn test_regex_natives() {
+    use super::call_native;
+
+    let string = |s: &str| Object::String(s.to_string());
+    let date = r"(?P<year>\d{4})-(?P<month>\d{2})(-(\d{2}))?";
+
+    assert_eq!(
+        call_native(regex_match, &[string(r"^\d+$"), string("123")]).unwrap(),
+        "true"
+    );
+    assert_eq!(
+        call_native(regex_find_all, &[string(r"\d+"), string("a1b22c333")]).unwrap(),
+        "[1, 22, 333]"
+    );
+    assert_eq!(
+        call_native(
+            regex_replace,
+            &[string(date), string("on 2024-05"), string("$month/$year")]
+        )
+        .unwrap(),
+        "on 05/2024"
+    );
+    assert_eq!(
+        call_native(regex_captures, &[string(date), string("2024-05")]).unwrap(),
+        "[2024-05, 2024, 05, nil, nil]"
+    );
+    assert_eq!(
+        call_native(
+            regex_named_captures,
+            &[string(date), string("x 1999-12-31")]
+        )
+        .unwrap(),
+        "{month: 12, year: 1999}"
+    );
+    assert_eq!(
+        call_native(regex_captures, &[string(date), string("no date")]).unwrap(),
+        "nil"
+    );
+
+    match call_native(regex_match, &[string("(unclosed"), string("")]) {
+        Err(LoxError::Runtime { message, .. }) => assert!(message.starts_with("Invalid regex:")),
+        other => panic!("Expected a runtime error, got {:?}", other),
+    }
+}
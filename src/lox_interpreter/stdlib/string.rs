@@ -0,0 +1,314 @@
+use std::{cell::RefCell, rc::Rc};
+
+use super::{define_native, integer_argument, runtime_error, string_argument};
+use crate::lox_interpreter::{
+    environment::Environment,
+    error::LoxError,
+    interpreter::{Interpreter, Object},
+    token::Token,
+};
+
+// NOTE: Every index and length here counts characters, not bytes, so `len("héllo")` is 5 and
+// `char_at("héllo", 1)` is "é".
+pub fn define(environment: &mut Environment) -> Result<(), LoxError> {
+    define_native(environment, "len", 1, 1, len)?;
+    define_native(environment, "substring", 2, 3, substring)?;
+    define_native(environment, "index_of", 2, 2, index_of)?;
+    define_native(environment, "split", 2, 2, split)?;
+    define_native(environment, "join", 2, 2, join)?;
+    define_native(environment, "trim", 1, 1, trim)?;
+    define_native(environment, "upper", 1, 1, upper)?;
+    define_native(environment, "lower", 1, 1, lower)?;
+    define_native(environment, "replace", 3, 3, replace)?;
+    define_native(environment, "starts_with", 2, 2, starts_with)?;
+    define_native(environment, "ends_with", 2, 2, ends_with)?;
+    define_native(environment, "repeat", 2, 2, repeat)?;
+    define_native(environment, "char_at", 2, 2, char_at)
+}
+
+// Works on lists and maps as well, it'd be odd to have a `len` that only knows about strings.
+fn len(_interpreter: &mut Interpreter, paren: &Token, args: &[Object]) -> Result<Object, LoxError> {
+    let length = match &args[0] {
+        Object::String(string) => string.chars().count(),
+        Object::List(list) => list.borrow().len(),
+        Object::Map(map) => map.borrow().len(),
+        _ => {
+            return runtime_error(
+                paren,
+                "Can only take the length of strings, lists and maps.".to_string(),
+            )
+        }
+    };
+    Ok(Object::Integer(length as i64))
+}
+
+// An index into a string of `length` characters. `end` allows pointing one past the last one.
+fn char_index(paren: &Token, index: i64, length: usize, end: bool) -> Result<usize, LoxError> {
+    let limit = if end { length + 1 } else { length };
+    if index < 0 || index as usize >= limit {
+        return runtime_error(
+            paren,
+            format!(
+                "Index {} out of range for string of length {}.",
+                index, length
+            ),
+        );
+    }
+    Ok(index as usize)
+}
+
+// substring(s, start) or substring(s, start, end), end is never included.
+fn substring(
+    _interpreter: &mut Interpreter,
+    paren: &Token,
+    args: &[Object],
+) -> Result<Object, LoxError> {
+    let string = string_argument(paren, args, 0)?;
+    let length = string.chars().count();
+    let start = char_index(paren, integer_argument(paren, args, 1)?, length, true)?;
+    let end = if args.len() == 3 {
+        char_index(paren, integer_argument(paren, args, 2)?, length, true)?
+    } else {
+        length
+    };
+
+    if start > end {
+        return runtime_error(
+            paren,
+            format!("Substring start {} is after its end {}.", start, end),
+        );
+    }
+    Ok(Object::String(
+        string.chars().skip(start).take(end - start).collect(),
+    ))
+}
+
+// The character index of the first occurrence, or -1 if there isn't one.
+fn index_of(
+    _interpreter: &mut Interpreter,
+    paren: &Token,
+    args: &[Object],
+) -> Result<Object, LoxError> {
+    let string = string_argument(paren, args, 0)?;
+    let needle = string_argument(paren, args, 1)?;
+
+    let index = match string.find(needle) {
+        Some(byte_index) => string[..byte_index].chars().count() as i64,
+        None => -1,
+    };
+    Ok(Object::Integer(index))
+}
+
+// An empty separator splits the string into its characters.
+fn split(
+    _interpreter: &mut Interpreter,
+    paren: &Token,
+    args: &[Object],
+) -> Result<Object, LoxError> {
+    let string = string_argument(paren, args, 0)?;
+    let separator = string_argument(paren, args, 1)?;
+
+    let parts: Vec<Object> = if separator.is_empty() {
+        string
+            .chars()
+            .map(|c| Object::String(c.to_string()))
+            .collect()
+    } else {
+        string
+            .split(separator)
+            .map(|part| Object::String(part.to_string()))
+            .collect()
+    };
+    Ok(Object::List(Rc::new(RefCell::new(parts))))
+}
+
+// join(list, separator), the elements are printed the same way print would.
+fn join(
+    _interpreter: &mut Interpreter,
+    paren: &Token,
+    args: &[Object],
+) -> Result<Object, LoxError> {
+    let Object::List(list) = &args[0] else {
+        return runtime_error(paren, "Argument 1 must be a list.".to_string());
+    };
+    let separator = string_argument(paren, args, 1)?;
+
+    let parts: Vec<String> = list.borrow().iter().map(|part| part.to_string()).collect();
+    Ok(Object::String(parts.join(separator)))
+}
+
+fn trim(
+    _interpreter: &mut Interpreter,
+    paren: &Token,
+    args: &[Object],
+) -> Result<Object, LoxError> {
+    Ok(Object::String(
+        string_argument(paren, args, 0)?.trim().to_string(),
+    ))
+}
+
+fn upper(
+    _interpreter: &mut Interpreter,
+    paren: &Token,
+    args: &[Object],
+) -> Result<Object, LoxError> {
+    Ok(Object::String(
+        string_argument(paren, args, 0)?.to_uppercase(),
+    ))
+}
+
+fn lower(
+    _interpreter: &mut Interpreter,
+    paren: &Token,
+    args: &[Object],
+) -> Result<Object, LoxError> {
+    Ok(Object::String(
+        string_argument(paren, args, 0)?.to_lowercase(),
+    ))
+}
+
+// Replaces every occurrence.
+fn replace(
+    _interpreter: &mut Interpreter,
+    paren: &Token,
+    args: &[Object],
+) -> Result<Object, LoxError> {
+    let string = string_argument(paren, args, 0)?;
+    let from = string_argument(paren, args, 1)?;
+    let to = string_argument(paren, args, 2)?;
+
+    if from.is_empty() {
+        return runtime_error(paren, "Cannot replace an empty string.".to_string());
+    }
+    Ok(Object::String(string.replace(from, to)))
+}
+
+fn starts_with(
+    _interpreter: &mut Interpreter,
+    paren: &Token,
+    args: &[Object],
+) -> Result<Object, LoxError> {
+    let string = string_argument(paren, args, 0)?;
+    let prefix = string_argument(paren, args, 1)?;
+    Ok(Object::Boolean(string.starts_with(prefix)))
+}
+
+fn ends_with(
+    _interpreter: &mut Interpreter,
+    paren: &Token,
+    args: &[Object],
+) -> Result<Object, LoxError> {
+    let string = string_argument(paren, args, 0)?;
+    let suffix = string_argument(paren, args, 1)?;
+    Ok(Object::Boolean(string.ends_with(suffix)))
+}
+
+// Longest string `repeat` will build, in bytes. Anything past this is a runaway count rather
+// than a string a script wants.
+const MAX_REPEAT_LENGTH: usize = 1 << 30;
+
+fn repeat(
+    _interpreter: &mut Interpreter,
+    paren: &Token,
+    args: &[Object],
+) -> Result<Object, LoxError> {
+    let string = string_argument(paren, args, 0)?;
+    let count = integer_argument(paren, args, 1)?;
+    if count < 0 {
+        return runtime_error(paren, "Repeat count cannot be negative.".to_string());
+    }
+    match string.len().checked_mul(count as usize) {
+        Some(length) if length <= MAX_REPEAT_LENGTH => {
+            Ok(Object::String(string.repeat(count as usize)))
+        }
+        _ => runtime_error(paren, "Repeated string would be too long.".to_string()),
+    }
+}
+
+fn char_at(
+    _interpreter: &mut Interpreter,
+    paren: &Token,
+    args: &[Object],
+) -> Result<Object, LoxError> {
+    let string = string_argument(paren, args, 0)?;
+    let index = char_index(
+        paren,
+        integer_argument(paren, args, 1)?,
+        string.chars().count(),
+        false,
+    )?;
+    Ok(Object::String(
+        string
+            .chars()
+            .nth(index)
+            .map(String::from)
+            .unwrap_or_default(),
+    ))
+}
+
+#[test]
+fn test_string_natives_count_characters() {
+    use super::call_native;
+
+    let string = |s: &str| Object::String(s.to_string());
+
+    assert_eq!(call_native(len, &[string("héllo wörld")]).unwrap(), "11");
+    assert_eq!(
+        call_native(
+            substring,
+            &[string("héllo"), Object::Integer(1), Object::Integer(3)]
+        )
+        .unwrap(),
+        "él"
+    );
+    assert_eq!(
+        call_native(index_of, &[string("añb"), string("b")]).unwrap(),
+        "2"
+    );
+    assert_eq!(
+        call_native(char_at, &[string("日本語"), Object::Integer(2)]).unwrap(),
+        "語"
+    );
+    assert_eq!(call_native(upper, &[string("straße")]).unwrap(), "STRASSE");
+    assert_eq!(
+        call_native(split, &[string("a,b,,c"), string(",")]).unwrap(),
+        "[a, b, , c]"
+    );
+    let parts = Object::List(Rc::new(RefCell::new(vec![string("ü"), Object::Integer(1)])));
+    assert_eq!(call_native(join, &[parts, string("-")]).unwrap(), "ü-1");
+    match call_native(char_at, &[string("héllo"), Object::Integer(5)]) {
+        Err(LoxError::Runtime { message, .. }) => {
+            assert_eq!(message, "Index 5 out of range for string of length 5.")
+        }
+        other => panic!("Expected a runtime error, got {:?}", other),
+    }
+    assert!(call_native(
+        substring,
+        &[string("abc"), Object::Integer(2), Object::Integer(1)]
+    )
+    .is_err());
+}
+
+#[test]
+fn test_repeat_refuses_huge_strings() {
+    use super::call_native;
+
+    let string = |s: &str| Object::String(s.to_string());
+
+    assert_eq!(
+        call_native(repeat, &[string("ab"), Object::Integer(3)]).unwrap(),
+        "ababab"
+    );
+    assert_eq!(
+        call_native(repeat, &[string(""), Object::Integer(i64::MAX)]).unwrap(),
+        ""
+    );
+    for count in [i64::MAX, 1 << 40] {
+        match call_native(repeat, &[string("ab"), Object::Integer(count)]) {
+            Err(LoxError::Runtime { message, .. }) => {
+                assert_eq!(message, "Repeated string would be too long.")
+            }
+            other => panic!("Expected a runtime error, got {:?}", other),
+        }
+    }
+}
@@ -0,0 +1,80 @@
+use std::env;
+
+use super::{check_allowed, define_native, runtime_error, string_argument};
+use crate::lox_interpreter::{
+    environment::Environment,
+    error::LoxError,
+    interpreter::{Interpreter, Object},
+    token::Token,
+};
+
+pub fn define(environment: &mut Environment) -> Result<(), LoxError> {
+    define_native(environment, "getenv", 1, 1, getenv)?;
+    define_native(environment, "setenv", 2, 2, setenv)
+}
+
+// The value of an environment variable, nil when it isn't set or isn't valid unicode.
+fn getenv(
+    interpreter: &mut Interpreter,
+    paren: &Token,
+    args: &[Object],
+) -> Result<Object, LoxError> {
+    check_allowed(interpreter, paren, "getenv")?;
+    let name = string_argument(paren, args, 0)?;
+    Ok(env::var(name).map_or(Object::NONE, Object::String))
+}
+
+// Sets the variable for the rest of the script and anything it starts.
+fn setenv(
+    interpreter: &mut Interpreter,
+    paren: &Token,
+    args: &[Object],
+) -> Result<Object, LoxError> {
+    check_allowed(interpreter, paren, "setenv")?;
+    let name = string_argument(paren, args, 0)?;
+    let value = string_argument(paren, args, 1)?;
+
+    // NOTE: set_var panics on these instead of returning an error.
+    if name.is_empty() || name.contains(['=', '\0']) {
+        return runtime_error(
+            paren,
+            format!("'{}' is not a valid environment variable name.", name),
+        );
+    }
+    if value.contains('\0') {
+        return runtime_error(
+            paren,
+            "Environment variable values can't contain a nul character.".to_string(),
+        );
+    }
+
+    env::set_var(name, value);
+    Ok(Object::NONE)
+}
+
+#[test]
+fn test_environment_variables() {
+    use super::call_native_in;
+
+    let mut interpreter = Interpreter::new();
+    let name = Object::String("RAIN_TEST_ENVIRONMENT_VARIABLE".to_string());
+
+    call_native_in(
+        &mut interpreter,
+        setenv,
+        &[name.clone(), Object::String("set".to_string())],
+    )
+    .unwrap();
+    assert_eq!(
+        call_native_in(&mut interpreter, getenv, std::slice::from_ref(&name))
+            .unwrap()
+            .to_string(),
+        "set"
+    );
+    assert!(call_native_in(
+        &mut interpreter,
+        setenv,
+        &[Object::String("A=B".to_string()), name]
+    )
+    .is_err());
+}
@@ -0,0 +1,316 @@
+use std::{
+    thread,
+    time::{Duration, Instant, SystemTime, UNIX_EPOCH},
+};
+
+use super::{define_native, integer_argument, number_argument, runtime_error, string_argument};
+use crate::lox_interpreter::{
+    environment::Environment,
+    error::LoxError,
+    interpreter::{Interpreter, Object},
+    token::Token,
+};
+
+const SECONDS_PER_DAY: i64 = 86_400;
+
+// NOTE: Dates are always UTC, there's no time zone database to look anything else up in.
+pub fn define(environment: &mut Environment) -> Result<(), LoxError> {
+    define_native(environment, "now", 0, 0, now)?;
+    define_native(environment, "sleep", 1, 1, sleep)?;
+    define_native(environment, "elapsed", 0, 1, elapsed)?;
+    define_native(environment, "format_time", 2, 2, format_time)?;
+    define_native(environment, "parse_time", 2, 2, parse_time)
+}
+
+// Seconds since the unix epoch, with the fraction.
+fn now(
+    _interpreter: &mut Interpreter,
+    paren: &Token,
+    _args: &[Object],
+) -> Result<Object, LoxError> {
+    match SystemTime::now().duration_since(UNIX_EPOCH) {
+        Ok(since_epoch) => Ok(Object::Number(since_epoch.as_secs_f64())),
+        Err(_) => runtime_error(paren, "System clock is set before 1970.".to_string()),
+    }
+}
+
+// sleep(ms). Sleeping past the interpreter's deadline only sleeps until it and then stops the
+// script, same as a loop running past it would.
+fn sleep(
+    interpreter: &mut Interpreter,
+    paren: &Token,
+    args: &[Object],
+) -> Result<Object, LoxError> {
+    let milliseconds = integer_argument(paren, args, 0)?;
+    if milliseconds < 0 {
+        return runtime_error(paren, "Cannot sleep for a negative time.".to_string());
+    }
+
+    let wake_up = Instant::now() + Duration::from_millis(milliseconds as u64);
+    match interpreter.deadline() {
+        Some(deadline) if deadline < wake_up => {
+            thread::sleep(deadline.saturating_duration_since(Instant::now()));
+            Err(LoxError::Timeout)
+        }
+        _ => {
+            thread::sleep(wake_up.saturating_duration_since(Instant::now()));
+            Ok(Object::NONE)
+        }
+    }
+}
+
+// Seconds on a clock that never goes backwards. elapsed() reads it, elapsed(start) gives the time
+// since an earlier reading.
+fn elapsed(
+    interpreter: &mut Interpreter,
+    paren: &Token,
+    args: &[Object],
+) -> Result<Object, LoxError> {
+    let seconds = interpreter.started().elapsed().as_secs_f64();
+    if args.is_empty() {
+        return Ok(Object::Number(seconds));
+    }
+    Ok(Object::Number(seconds - number_argument(paren, args, 0)?))
+}
+
+// format_time(timestamp, format) with strftime style directives: %Y %m %d %H %M %S %j and %%.
+fn format_time(
+    _interpreter: &mut Interpreter,
+    paren: &Token,
+    args: &[Object],
+) -> Result<Object, LoxError> {
+    let timestamp = number_argument(paren, args, 0)?.floor();
+    let format = string_argument(paren, args, 1)?;
+    if !timestamp.is_finite() || timestamp.abs() > 1e15 {
+        return runtime_error(paren, format!("Timestamp {} is out of range.", timestamp));
+    }
+
+    let timestamp = timestamp as i64;
+    let days = timestamp.div_euclid(SECONDS_PER_DAY);
+    let seconds = timestamp.rem_euclid(SECONDS_PER_DAY);
+    let (year, month, day) = civil_from_days(days);
+
+    let mut formatted = String::new();
+    let mut chars = format.chars();
+    while let Some(c) = chars.next() {
+        if c != '%' {
+            formatted.push(c);
+            continue;
+        }
+        match chars.next() {
+            Some('Y') => formatted.push_str(&format!("{:04}", year)),
+            Some('m') => formatted.push_str(&format!("{:02}", month)),
+            Some('d') => formatted.push_str(&format!("{:02}", day)),
+            Some('H') => formatted.push_str(&format!("{:02}", seconds / 3600)),
+            Some('M') => formatted.push_str(&format!("{:02}", seconds / 60 % 60)),
+            Some('S') => formatted.push_str(&format!("{:02}", seconds % 60)),
+            Some('j') => {
+                formatted.push_str(&format!("{:03}", days - days_from_civil(year, 1, 1) + 1))
+            }
+            Some('%') => formatted.push('%'),
+            other => return unknown_directive(paren, other),
+        }
+    }
+    Ok(Object::String(formatted))
+}
+
+// parse_time(text, format) is the reverse of format_time, it gives back the timestamp in whole
+// seconds. Fields the format leaves out default to 1970-01-01 00:00:00.
+fn parse_time(
+    _interpreter: &mut Interpreter,
+    paren: &Token,
+    args: &[Object],
+) -> Result<Object, LoxError> {
+    let text = string_argument(paren, args, 0)?;
+    let format = string_argument(paren, args, 1)?;
+    let mismatch = || {
+        runtime_error(
+            paren,
+            format!("'{}' doesn't match the format '{}'.", text, format),
+        )
+    };
+
+    let (mut year, mut month, mut day) = (1970, 1, 1);
+    let (mut hour, mut minute, mut second) = (0, 0, 0);
+    let mut day_of_year = None;
+
+    let text: Vec<char> = text.chars().collect();
+    let mut position = 0;
+    let mut directives = format.chars();
+    while let Some(c) = directives.next() {
+        if c != '%' {
+            if text.get(position) != Some(&c) {
+                return mismatch();
+            }
+            position += 1;
+            continue;
+        }
+
+        let directive = directives.next();
+        if directive == Some('%') {
+            if text.get(position) != Some(&'%') {
+                return mismatch();
+            }
+            position += 1;
+            continue;
+        }
+        let (min_digits, max_digits) = match directive {
+            Some('Y') => (4, 4),
+            Some('m' | 'd' | 'H' | 'M' | 'S') => (2, 2),
+            Some('j') => (3, 3),
+            other => return unknown_directive(paren, other),
+        };
+        let Some((value, length)) = digits(&text[position..], min_digits, max_digits) else {
+            return mismatch();
+        };
+        position += length;
+
+        match directive {
+            Some('Y') => year = value,
+            Some('m') => month = value,
+            Some('d') => day = value,
+            Some('H') => hour = value,
+            Some('M') => minute = value,
+            Some('S') => second = value,
+            _ => day_of_year = Some(value),
+        }
+    }
+    if position != text.len() {
+        return mismatch();
+    }
+
+    let days_in_month = match month {
+        2 if is_leap_year(year) => 29,
+        2 => 28,
+        4 | 6 | 9 | 11 => 30,
+        _ => 31,
+    };
+    let days_in_year = if is_leap_year(year) { 366 } else { 365 };
+    let valid = (1..=12).contains(&month)
+        && (1..=days_in_month).contains(&day)
+        && hour < 24
+        && minute < 60
+        && second < 60
+        && day_of_year.is_none_or(|day_of_year| (1..=days_in_year).contains(&day_of_year));
+    if !valid {
+        return runtime_error(
+            paren,
+            format!("'{}' is not a valid date.", text.iter().collect::<String>()),
+        );
+    }
+
+    let days = match day_of_year {
+        Some(day_of_year) => days_from_civil(year, 1, 1) + day_of_year - 1,
+        None => days_from_civil(year, month, day),
+    };
+    Ok(Object::Integer(
+        days * SECONDS_PER_DAY + hour * 3600 + minute * 60 + second,
+    ))
+}
+
+fn unknown_directive<T>(paren: &Token, directive: Option<char>) -> Result<T, LoxError> {
+    match directive {
+        Some(directive) => {
+            runtime_error(paren, format!("Unknown format directive '%{}'.", directive))
+        }
+        None => runtime_error(paren, "Format can't end with a lone '%'.".to_string()),
+    }
+}
+
+// Reads between min and max ascii digits off the front of the text, along with how many it read.
+fn digits(text: &[char], min: usize, max: usize) -> Option<(i64, usize)> {
+    let length = text
+        .iter()
+        .take(max)
+        .take_while(|c| c.is_ascii_digit())
+        .count();
+    if length < min {
+        return None;
+    }
+    let value = text[..length].iter().fold(0, |value, c| {
+        value * 10 + c.to_digit(10).unwrap_or(0) as i64
+    });
+    Some((value, length))
+}
+
+fn is_leap_year(year: i64) -> bool {
+    (year % 4 == 0 && year % 100 != 0) || year % 400 == 0
+}
+
+// Days since 1970-01-01 in the proleptic Gregorian calendar, from Howard Hinnant's date
+// algorithms.
+fn days_from_civil(year: i64, month: i64, day: i64) -> i64 {
+    let year = if month <= 2 { year - 1 } else { year };
+    let era = year.div_euclid(400);
+    let year_of_era = year - era * 400;
+    let day_of_year = (153 * ((month + 9) % 12) + 2) / 5 + day - 1;
+    let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+    era * 146_097 + day_of_era - 719_468
+}
+
+fn civil_from_days(days: i64) -> (i64, i64, i64) {
+    let days = days + 719_468;
+    let era = days.div_euclid(146_097);
+    let day_of_era = days - era * 146_097;
+    let year_of_era =
+        (day_of_era - day_of_era / 1460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let month_index = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * month_index + 2) / 5 + 1;
+    let month = if month_index < 10 {
+        month_index + 3
+    } else {
+        month_index - 9
+    };
+    let year = year_of_era + era * 400 + if month <= 2 { 1 } else { 0 };
+    (year, month, day)
+}
+
+#[test]
+fn test_format_and_parse_time() {
+    use super::{call_native, call_native_in};
+
+    let string = |s: &str| Object::String(s.to_string());
+
+    let format = "%Y-%m-%d %H:%M:%S (day %j) 100%%";
+    // 2024-02-29 13:05:09 UTC, a leap day.
+    assert_eq!(
+        call_native(
+            format_time,
+            &[Object::Integer(1_709_211_909), string(format)]
+        )
+        .unwrap(),
+        "2024-02-29 13:05:09 (day 060) 100%"
+    );
+    assert_eq!(
+        call_native(
+            parse_time,
+            &[string("2024-02-29 13:05:09 (day 060) 100%"), string(format)]
+        )
+        .unwrap(),
+        "1709211909"
+    );
+    assert_eq!(
+        call_native(
+            format_time,
+            &[Object::Number(-0.5), string("%Y-%m-%d %H:%M:%S")]
+        )
+        .unwrap(),
+        "1969-12-31 23:59:59"
+    );
+    assert_eq!(
+        call_native(parse_time, &[string("1970-01-02"), string("%Y-%m-%d")]).unwrap(),
+        "86400"
+    );
+
+    assert!(call_native(parse_time, &[string("2023-02-29"), string("%Y-%m-%d")]).is_err());
+    assert!(call_native(parse_time, &[string("2023-02"), string("%Y-%m-%d")]).is_err());
+    assert!(call_native(format_time, &[Object::Integer(0), string("%q")]).is_err());
+
+    let mut late = Interpreter::new();
+    late.set_deadline(Some(Instant::now()));
+    assert!(matches!(
+        call_native_in(&mut late, sleep, &[Object::Integer(1000)]),
+        Err(LoxError::Timeout)
+    ));
+}
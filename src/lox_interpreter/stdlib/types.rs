@@ -0,0 +1,101 @@
+use super::{define_native, runtime_error};
+use crate::lox_interpreter::{
+    environment::Environment,
+    error::LoxError,
+    interpreter::{Interpreter, Object},
+    token::Token,
+};
+
+pub fn define(environment: &mut Environment) -> Result<(), LoxError> {
+    define_native(environment, "type", 1, 1, type_of)?;
+    define_native(environment, "str", 1, 1, str)?;
+    define_native(environment, "num", 1, 1, num)?;
+    define_native(environment, "bool", 1, 1, bool)
+}
+
+// Integers and floats are both "number", scripts don't get to tell them apart any other way
+// either.
+fn type_of(
+    _interpreter: &mut Interpreter,
+    _paren: &Token,
+    args: &[Object],
+) -> Result<Object, LoxError> {
+    let name = match &args[0] {
+        Object::NONE => "nil",
+        Object::Boolean(_) => "bool",
+        Object::Number(_) | Object::Integer(_) => "number",
+        Object::String(_) => "string",
+        Object::Callable(_) | Object::NativeFunction(_) => "function",
+        Object::List(_) => "list",
+        Object::Map(_) => "map",
+        Object::Range(_) => "range",
+    };
+    Ok(Object::String(name.to_string()))
+}
+
+// The same text print would show.
+fn str(
+    _interpreter: &mut Interpreter,
+    _paren: &Token,
+    args: &[Object],
+) -> Result<Object, LoxError> {
+    Ok(Object::String(args[0].to_string()))
+}
+
+// Numbers come back as they are. Strings without a fraction or exponent become integers, the rest
+// floats, leading and trailing whitespace is ignored.
+fn num(_interpreter: &mut Interpreter, paren: &Token, args: &[Object]) -> Result<Object, LoxError> {
+    let text = match &args[0] {
+        number @ (Object::Integer(_) | Object::Number(_)) => return Ok(number.clone()),
+        Object::String(text) => text.trim(),
+        other => return runtime_error(paren, format!("Cannot convert {} to a number.", other)),
+    };
+
+    if let Ok(integer) = text.parse::<i64>() {
+        return Ok(Object::Integer(integer));
+    }
+    // NOTE: Rust happily parses "inf" and "NaN", neither of which a script could write itself.
+    match text.parse::<f64>() {
+        Ok(number) if number.is_finite() => Ok(Object::Number(number)),
+        _ => runtime_error(paren, format!("Cannot convert '{}' to a number.", text)),
+    }
+}
+
+// Same truthiness as `if` and `!`, only nil and false are false.
+fn bool(
+    interpreter: &mut Interpreter,
+    _paren: &Token,
+    args: &[Object],
+) -> Result<Object, LoxError> {
+    Ok(Object::Boolean(interpreter.is_truthly(&args[0])))
+}
+
+#[test]
+fn test_type_and_conversions() {
+    use super::call_native;
+    use std::{cell::RefCell, rc::Rc};
+
+    let string = |s: &str| Object::String(s.to_string());
+
+    assert_eq!(
+        call_native(type_of, &[Object::Integer(1)]).unwrap(),
+        "number"
+    );
+    assert_eq!(call_native(type_of, &[Object::NONE]).unwrap(), "nil");
+    assert_eq!(
+        call_native(type_of, &[Object::List(Rc::new(RefCell::new(Vec::new())))]).unwrap(),
+        "list"
+    );
+    assert_eq!(call_native(str, &[Object::NONE]).unwrap(), "nil");
+    assert_eq!(call_native(num, &[string(" 42 ")]).unwrap(), "42");
+    assert_eq!(call_native(num, &[string("2.5e1")]).unwrap(), "25");
+    assert_eq!(call_native(bool, &[Object::Integer(0)]).unwrap(), "true");
+    assert_eq!(call_native(bool, &[Object::NONE]).unwrap(), "false");
+
+    match call_native(num, &[string("NaN")]) {
+        Err(LoxError::Runtime { message, .. }) => {
+            assert_eq!(message, "Cannot convert 'NaN' to a number.")
+        }
+        other => panic!("Expected a runtime error, got {:?}", other),
+    }
+}
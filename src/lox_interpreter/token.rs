@@ -9,6 +9,8 @@ pub enum TokenType {
     RIGHT_PAREN,
     LEFT_BRACE,
     RIGHT_BRACE,
+    LEFT_BRACKET,
+    RIGHT_BRACKET,
     COMMA,
     DOT,
     MINUS,
@@ -16,6 +18,11 @@ pub enum TokenType {
     SEMICOLON,
     SLASH,
     STAR,
+    PERCENT,
+    AMPERSAND,
+    PIPE,
+    CARET,
+    COLON,
 
     // One or two character tokens.
     BANG,
@@ -26,6 +33,23 @@ pub enum TokenType {
     GREATER_EQUAL,
     LESS,
     LESS_EQUAL,
+    STAR_STAR,
+    TILDE_SLASH,
+    LESS_LESS,
+    GREATER_GREATER,
+    PLUS_EQUAL,
+    MINUS_EQUAL,
+    STAR_EQUAL,
+    SLASH_EQUAL,
+    PLUS_PLUS,
+    MINUS_MINUS,
+    QUESTION,
+    QUESTION_QUESTION,
+    QUESTION_DOT,
+    FAT_ARROW,
+    DOT_DOT,
+    DOT_DOT_EQUAL,
+    DOT_DOT_DOT,
 
     // Literals.
     IDENTIFIER,
@@ -52,10 +76,96 @@ pub enum TokenType {
     EOF,
     BREAK,
     CONTINUE,
+    IN,
+    DO,
+    LOOP,
+    MATCH,
+    CASE,
+    CONST,
+}
+
+impl TokenType {
+    // The other way around from the Debug name, for reading tokens back in from JSON.
+    pub fn from_name(name: &str) -> Option<TokenType> {
+        Some(match name {
+            "LEFT_PAREN" => TokenType::LEFT_PAREN,
+            "RIGHT_PAREN" => TokenType::RIGHT_PAREN,
+            "LEFT_BRACE" => TokenType::LEFT_BRACE,
+            "RIGHT_BRACE" => TokenType::RIGHT_BRACE,
+            "LEFT_BRACKET" => TokenType::LEFT_BRACKET,
+            "RIGHT_BRACKET" => TokenType::RIGHT_BRACKET,
+            "COMMA" => TokenType::COMMA,
+            "DOT" => TokenType::DOT,
+            "MINUS" => TokenType::MINUS,
+            "PLUS" => TokenType::PLUS,
+            "SEMICOLON" => TokenType::SEMICOLON,
+            "SLASH" => TokenType::SLASH,
+            "STAR" => TokenType::STAR,
+            "PERCENT" => TokenType::PERCENT,
+            "AMPERSAND" => TokenType::AMPERSAND,
+            "PIPE" => TokenType::PIPE,
+            "CARET" => TokenType::CARET,
+            "COLON" => TokenType::COLON,
+            "BANG" => TokenType::BANG,
+            "BANG_EQUAL" => TokenType::BANG_EQUAL,
+            "EQUAL" => TokenType::EQUAL,
+            "EQUAL_EQUAL" => TokenType::EQUAL_EQUAL,
+            "GREATER" => TokenType::GREATER,
+            "GREATER_EQUAL" => TokenType::GREATER_EQUAL,
+            "LESS" => TokenType::LESS,
+            "LESS_EQUAL" => TokenType::LESS_EQUAL,
+            "STAR_STAR" => TokenType::STAR_STAR,
+            "TILDE_SLASH" => TokenType::TILDE_SLASH,
+            "LESS_LESS" => TokenType::LESS_LESS,
+            "GREATER_GREATER" => TokenType::GREATER_GREATER,
+            "PLUS_EQUAL" => TokenType::PLUS_EQUAL,
+            "MINUS_EQUAL" => TokenType::MINUS_EQUAL,
+            "STAR_EQUAL" => TokenType::STAR_EQUAL,
+            "SLASH_EQUAL" => TokenType::SLASH_EQUAL,
+            "PLUS_PLUS" => TokenType::PLUS_PLUS,
+            "MINUS_MINUS" => TokenType::MINUS_MINUS,
+            "QUESTION" => TokenType::QUESTION,
+            "QUESTION_QUESTION" => TokenType::QUESTION_QUESTION,
+            "QUESTION_DOT" => TokenType::QUESTION_DOT,
+            "FAT_ARROW" => TokenType::FAT_ARROW,
+            "DOT_DOT" => TokenType::DOT_DOT,
+            "DOT_DOT_EQUAL" => TokenType::DOT_DOT_EQUAL,
+            "DOT_DOT_DOT" => TokenType::DOT_DOT_DOT,
+            "IDENTIFIER" => TokenType::IDENTIFIER,
+            "STRING" => TokenType::STRING,
+            "NUMBER" => TokenType::NUMBER,
+            "AND" => TokenType::AND,
+            "CLASS" => TokenType::CLASS,
+            "ELSE" => TokenType::ELSE,
+            "FALSE" => TokenType::FALSE,
+            "FUN" => TokenType::FUN,
+            "FOR" => TokenType::FOR,
+            "IF" => TokenType::IF,
+            "NONE" => TokenType::NONE,
+            "OR" => TokenType::OR,
+            "PRINT" => TokenType::PRINT,
+            "RETURN" => TokenType::RETURN,
+            "SUPER" => TokenType::SUPER,
+            "THIS" => TokenType::THIS,
+            "TRUE" => TokenType::TRUE,
+            "VAR" => TokenType::VAR,
+            "WHILE" => TokenType::WHILE,
+            "EOF" => TokenType::EOF,
+            "BREAK" => TokenType::BREAK,
+            "CONTINUE" => TokenType::CONTINUE,
+            "IN" => TokenType::IN,
+            "DO" => TokenType::DO,
+            "LOOP" => TokenType::LOOP,
+            "MATCH" => TokenType::MATCH,
+            "CASE" => TokenType::CASE,
+            "CONST" => TokenType::CONST,
+            _ => return None,
+        })
+    }
 }
 
 // TODO: Check if this is the correct way to do this.
-const KEYWORDS: [(&str, TokenType); 18] = [
+const KEYWORDS: [(&str, TokenType); 24] = [
     ("and", TokenType::AND),
     ("class", TokenType::CLASS),
     ("else", TokenType::ELSE),
@@ -74,6 +184,12 @@ const KEYWORDS: [(&str, TokenType); 18] = [
     ("while", TokenType::WHILE),
     ("break", TokenType::BREAK),
     ("continue", TokenType::CONTINUE),
+    ("in", TokenType::IN),
+    ("do", TokenType::DO),
+    ("loop", TokenType::LOOP),
+    ("match", TokenType::MATCH),
+    ("case", TokenType::CASE),
+    ("const", TokenType::CONST),
 ];
 
 pub fn lookup_keyword(keyword: String) -> Option<TokenType> {
@@ -89,6 +205,7 @@ pub fn lookup_keyword(keyword: String) -> Option<TokenType> {
 pub enum Literal {
     String(String),
     Float(f64),
+    Integer(i64),
     None,
     Boolean(bool),
 }
@@ -99,17 +216,17 @@ impl fmt::Display for Literal {
             Literal::Boolean(bool) => write!(f, "{}", bool),
             Literal::None => write!(f, "none"),
             Literal::Float(float) => write!(f, "{}", float),
+            Literal::Integer(integer) => write!(f, "{}", integer),
             Literal::String(string) => write!(f, "{}", string),
         }
     }
 }
 
-#[allow(dead_code)]
 #[derive(Debug, Clone)]
 pub struct Token {
     pub token_type: TokenType,
     pub lexeme: String,
-    literal: Literal,
+    pub literal: Literal,
     pub line: usize,
     pub column: usize,
 }
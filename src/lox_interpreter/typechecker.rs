@@ -0,0 +1,774 @@
+use std::{collections::HashMap, fmt, rc::Rc};
+
+use super::{
+    ast_tools::{
+        expr, stmt, Argument, BindingPattern, Expr, MatchArm, Parameter, Pattern, Stmt,
+        TypeAnnotation,
+    },
+    error::{report_type_error, LoxError},
+    token::{Literal, Token, TokenType},
+};
+
+// The types an annotation can name. Integers and floats are both a Number. Whatever the checker
+// can't work out is Any, which goes with everything, so unannotated code is never rejected.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Type {
+    Any,
+    Nil,
+    Bool,
+    Number,
+    String,
+    List,
+    Map,
+    Function,
+}
+
+impl Type {
+    pub fn from_name(name: &str) -> Option<Type> {
+        match name {
+            "Any" => Some(Type::Any),
+            "Nil" => Some(Type::Nil),
+            "Bool" => Some(Type::Bool),
+            "Number" => Some(Type::Number),
+            "String" => Some(Type::String),
+            "List" => Some(Type::List),
+            "Map" => Some(Type::Map),
+            "Function" => Some(Type::Function),
+            _ => None,
+        }
+    }
+
+    // Whether a value of type `actual` can go where `self` is expected.
+    fn accepts(self, actual: Type) -> bool {
+        self == Type::Any || actual == Type::Any || self == actual
+    }
+
+    fn is_known(self) -> bool {
+        self != Type::Any
+    }
+}
+
+impl fmt::Display for Type {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:?}", self)
+    }
+}
+
+// What we know about a declared function, so calls to it can be checked.
+struct Signature {
+    name: String,
+    // Every parameter but the rest one, unannotated ones are Any.
+    params: Vec<(Option<String>, Type)>,
+    returns: Type,
+}
+
+#[derive(Clone)]
+struct Binding {
+    ty: Type,
+    signature: Option<Rc<Signature>>,
+}
+
+// Checks annotated code before it runs. Variables only get a type from their annotation, or for
+// constants from their value, since a plain var can be reassigned to anything later on.
+pub struct TypeChecker {
+    // Mirrors the environments the interpreter will create, innermost last.
+    scopes: Vec<HashMap<String, Binding>>,
+    // The function being checked and what it should return, innermost last.
+    functions: Vec<(String, Type)>,
+    had_error: bool,
+}
+
+impl Default for TypeChecker {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl TypeChecker {
+    pub fn new() -> Self {
+        TypeChecker {
+            // The global scope.
+            scopes: vec![HashMap::new()],
+            functions: Vec::new(),
+            had_error: false,
+        }
+    }
+
+    pub fn check(&mut self, statements: &[Stmt]) -> Result<(), LoxError> {
+        for statement in statements {
+            self.check_stmt(statement)?;
+        }
+
+        if self.had_error {
+            return Err(LoxError::TypeCheck);
+        }
+        Ok(())
+    }
+
+    fn check_stmt(&mut self, statement: &Stmt) -> Result<(), LoxError> {
+        statement.accept(self)
+    }
+
+    fn check_expr(&mut self, expression: &Expr) -> Result<Type, LoxError> {
+        expression.accept(self)
+    }
+
+    fn error(&mut self, token: &Token, message: &str) {
+        report_type_error(token, message);
+        self.had_error = true;
+    }
+
+    fn scoped<T>(&mut self, check: impl FnOnce(&mut Self) -> T) -> T {
+        self.scopes.push(HashMap::new());
+        let result = check(self);
+        self.scopes.pop();
+        result
+    }
+
+    fn declare(&mut self, name: &Token, ty: Type, signature: Option<Rc<Signature>>) {
+        if let Some(scope) = self.scopes.last_mut() {
+            scope.insert(name.lexeme.clone(), Binding { ty, signature });
+        }
+    }
+
+    // Every name in a destructuring pattern is Any, we don't know what's inside lists and maps.
+    fn declare_target(&mut self, target: &BindingPattern, ty: Type) {
+        match target {
+            BindingPattern::Name(name) => self.declare(name, ty, None),
+            pattern => {
+                for name in pattern.names() {
+                    self.declare(name, Type::Any, None);
+                }
+            }
+        }
+    }
+
+    fn lookup(&self, name: &Token) -> Option<&Binding> {
+        self.scopes
+            .iter()
+            .rev()
+            .find_map(|scope| scope.get(&name.lexeme))
+    }
+
+    fn check_annotation(
+        &mut self,
+        token: &Token,
+        annotation: &Option<TypeAnnotation>,
+        actual: Type,
+    ) {
+        if let Some(annotation) = annotation {
+            if !annotation.ty.accepts(actual) {
+                self.error(
+                    token,
+                    &format!(
+                        "Cannot initialize '{}' of type {} with a {}.",
+                        token.lexeme, annotation.ty, actual
+                    ),
+                );
+            }
+        }
+    }
+
+    fn check_number_operand(&mut self, operator: &Token, operand: Type) {
+        if operand.is_known() && operand != Type::Number {
+            self.error(
+                operator,
+                &format!(
+                    "Operand of '{}' must be a Number, but got {}.",
+                    operator.lexeme, operand
+                ),
+            );
+        }
+    }
+
+    // `kind` is the operator that actually gets applied, for `+=` it's `+`.
+    fn binary_type(&mut self, left: Type, operator: &Token, kind: TokenType, right: Type) -> Type {
+        match kind {
+            TokenType::BANG_EQUAL | TokenType::EQUAL_EQUAL => Type::Bool,
+            TokenType::LESS
+            | TokenType::LESS_EQUAL
+            | TokenType::GREATER
+            | TokenType::GREATER_EQUAL
+            | TokenType::PLUS => {
+                let result = match (left, right) {
+                    (Type::Number, Type::Number) => Type::Number,
+                    (Type::String, Type::String) => Type::String,
+                    (Type::Any, _) | (_, Type::Any) => Type::Any,
+                    _ => {
+                        self.error(
+                            operator,
+                            &format!(
+                                "Operands of '{}' must be two Numbers or two Strings, but got {} and {}.",
+                                operator.lexeme, left, right
+                            ),
+                        );
+                        Type::Any
+                    }
+                };
+                if kind == TokenType::PLUS {
+                    result
+                } else {
+                    Type::Bool
+                }
+            }
+            _ => {
+                let is_number = |operand: Type| !operand.is_known() || operand == Type::Number;
+                if !is_number(left) || !is_number(right) {
+                    self.error(
+                        operator,
+                        &format!(
+                            "Operands of '{}' must be Numbers, but got {} and {}.",
+                            operator.lexeme, left, right
+                        ),
+                    );
+                }
+                Type::Number
+            }
+        }
+    }
+
+    fn check_arguments(
+        &mut self,
+        signature: &Signature,
+        paren: &Token,
+        arguments: &[Argument],
+        types: &[Type],
+    ) {
+        let mut positional = 0;
+        for (argument, actual) in arguments.iter().zip(types) {
+            let (token, expected) = match &argument.name {
+                Some(name) => {
+                    let expected = signature
+                        .params
+                        .iter()
+                        .find(|(param, _)| param.as_ref() == Some(&name.lexeme));
+                    (name, expected)
+                }
+                None => {
+                    positional += 1;
+                    (paren, signature.params.get(positional - 1))
+                }
+            };
+
+            if let Some((param, expected)) = expected {
+                if !expected.accepts(*actual) {
+                    let param = param.as_deref().unwrap_or("_");
+                    self.error(
+                        token,
+                        &format!(
+                            "Argument '{}' of '{}' expects {}, but got {}.",
+                            param, signature.name, expected, actual
+                        ),
+                    );
+                }
+            }
+        }
+    }
+
+    fn check_container(&mut self, token: &Token, object: Type, allowed: &[Type], what: &str) {
+        if object.is_known() && !allowed.contains(&object) {
+            self.error(token, &format!("{}, but got {}.", what, object));
+        }
+    }
+}
+
+impl expr::Visitor<Type> for TypeChecker {
+    fn visit_binary_expr(
+        &mut self,
+        left: &Expr,
+        operator: &Token,
+        right: &Expr,
+    ) -> Result<Type, LoxError> {
+        let left = self.check_expr(left)?;
+        let right = self.check_expr(right)?;
+        Ok(self.binary_type(left, operator, operator.token_type.clone(), right))
+    }
+
+    fn visit_grouping_expr(&mut self, expression: &Expr) -> Result<Type, LoxError> {
+        self.check_expr(expression)
+    }
+
+    fn visit_literal_expr(&mut self, value: &Literal) -> Result<Type, LoxError> {
+        Ok(match value {
+            Literal::String(_) => Type::String,
+            Literal::Float(_) | Literal::Integer(_) => Type::Number,
+            Literal::Boolean(_) => Type::Bool,
+            Literal::None => Type::Nil,
+        })
+    }
+
+    fn visit_unary_expr(&mut self, operator: &Token, right: &Expr) -> Result<Type, LoxError> {
+        let right = self.check_expr(right)?;
+        match operator.token_type {
+            TokenType::BANG => Ok(Type::Bool),
+            _ => {
+                self.check_number_operand(operator, right);
+                Ok(Type::Number)
+            }
+        }
+    }
+
+    fn visit_variable_expr(&mut self, name: &Token) -> Result<Type, LoxError> {
+        Ok(self.lookup(name).map_or(Type::Any, |binding| binding.ty))
+    }
+
+    fn visit_assignment_expr(&mut self, name: &Token, value: &Expr) -> Result<Type, LoxError> {
+        let value = self.check_expr(value)?;
+        let declared = self.lookup(name).map_or(Type::Any, |binding| binding.ty);
+        if !declared.accepts(value) {
+            self.error(
+                name,
+                &format!(
+                    "Cannot assign a {} to '{}' of type {}.",
+                    value, name.lexeme, declared
+                ),
+            );
+        }
+        Ok(value)
+    }
+
+    fn visit_logical_expr(
+        &mut self,
+        left: &Expr,
+        operator: &Token,
+        right: &Expr,
+    ) -> Result<Type, LoxError> {
+        let left = self.check_expr(left)?;
+        let right = self.check_expr(right)?;
+        Ok(match (&operator.token_type, left, right) {
+            (TokenType::QUESTION_QUESTION, Type::Nil, right) => right,
+            (_, left, right) if left == right => left,
+            _ => Type::Any,
+        })
+    }
+
+    fn visit_call_expr(
+        &mut self,
+        callee: &Expr,
+        paren: &Token,
+        arguments: &Vec<Argument>,
+    ) -> Result<Type, LoxError> {
+        let callee_type = self.check_expr(callee)?;
+        let signature = match callee {
+            Expr::Variable { name } => self
+                .lookup(name)
+                .and_then(|binding| binding.signature.clone()),
+            _ => None,
+        };
+
+        let mut types = Vec::with_capacity(arguments.len());
+        for argument in arguments {
+            types.push(self.check_expr(&argument.value)?);
+        }
+
+        match signature {
+            Some(signature) => {
+                self.check_arguments(&signature, paren, arguments, &types);
+                Ok(signature.returns)
+            }
+            None => {
+                self.check_container(
+                    paren,
+                    callee_type,
+                    &[Type::Function],
+                    "Can only call functions",
+                );
+                Ok(Type::Any)
+            }
+        }
+    }
+
+    fn visit_compound_assign_expr(
+        &mut self,
+        target: &Expr,
+        operator: &Token,
+        value: &Expr,
+    ) -> Result<Type, LoxError> {
+        let current = self.check_expr(target)?;
+        let value = self.check_expr(value)?;
+        let kind = match operator.token_type {
+            TokenType::PLUS_EQUAL => TokenType::PLUS,
+            TokenType::MINUS_EQUAL => TokenType::MINUS,
+            TokenType::STAR_EQUAL => TokenType::STAR,
+            _ => TokenType::SLASH,
+        };
+        Ok(self.binary_type(current, operator, kind, value))
+    }
+
+    fn visit_increment_expr(
+        &mut self,
+        target: &Expr,
+        operator: &Token,
+        _prefix: bool,
+    ) -> Result<Type, LoxError> {
+        let current = self.check_expr(target)?;
+        self.check_number_operand(operator, current);
+        Ok(Type::Number)
+    }
+
+    fn visit_conditional_expr(
+        &mut self,
+        condition: &Expr,
+        then_branch: &Expr,
+        else_branch: &Expr,
+    ) -> Result<Type, LoxError> {
+        self.check_expr(condition)?;
+        let then_type = self.check_expr(then_branch)?;
+        let else_type = self.check_expr(else_branch)?;
+        Ok(if then_type == else_type {
+            then_type
+        } else {
+            Type::Any
+        })
+    }
+
+    fn visit_get_expr(
+        &mut self,
+        object: &Expr,
+        name: &Token,
+        optional: bool,
+    ) -> Result<Type, LoxError> {
+        let object = self.check_expr(object)?;
+        if !(optional && object == Type::Nil) {
+            self.check_container(name, object, &[Type::Map], "Only maps have properties");
+        }
+        Ok(Type::Any)
+    }
+
+    fn visit_list_expr(&mut self, elements: &Vec<Expr>) -> Result<Type, LoxError> {
+        for element in elements {
+            self.check_expr(element)?;
+        }
+        Ok(Type::List)
+    }
+
+    fn visit_index_expr(
+        &mut self,
+        object: &Expr,
+        bracket: &Token,
+        index: &Expr,
+    ) -> Result<Type, LoxError> {
+        let object = self.check_expr(object)?;
+        self.check_expr(index)?;
+        self.check_container(
+            bracket,
+            object,
+            &[Type::List, Type::Map],
+            "Only lists and maps can be indexed",
+        );
+        Ok(Type::Any)
+    }
+
+    fn visit_set_index_expr(
+        &mut self,
+        object: &Expr,
+        bracket: &Token,
+        index: &Expr,
+        value: &Expr,
+    ) -> Result<Type, LoxError> {
+        let object = self.check_expr(object)?;
+        self.check_expr(index)?;
+        self.check_container(
+            bracket,
+            object,
+            &[Type::List, Type::Map],
+            "Only lists and maps can be indexed",
+        );
+        self.check_expr(value)
+    }
+
+    fn visit_map_expr(
+        &mut self,
+        _brace: &Token,
+        entries: &Vec<(Token, Expr)>,
+    ) -> Result<Type, LoxError> {
+        for (_, value) in entries {
+            self.check_expr(value)?;
+        }
+        Ok(Type::Map)
+    }
+
+    fn visit_destructure_assign_expr(
+        &mut self,
+        pattern: &BindingPattern,
+        equals: &Token,
+        value: &Expr,
+    ) -> Result<Type, LoxError> {
+        let value = self.check_expr(value)?;
+        let expected = match pattern {
+            BindingPattern::List { .. } => Type::List,
+            _ => Type::Map,
+        };
+        if !expected.accepts(value) {
+            self.error(
+                equals,
+                &format!("Cannot destructure a {} as a {}.", value, expected),
+            );
+        }
+        Ok(value)
+    }
+}
+
+impl stmt::Visitor<()> for TypeChecker {
+    fn visit_block_stmt(&mut self, statements: &Vec<Stmt>) -> Result<(), LoxError> {
+        self.scoped(|checker| {
+            statements
+                .iter()
+                .try_for_each(|statement| checker.check_stmt(statement))
+        })
+    }
+
+    fn visit_expression_stmt(&mut self, expression: &Expr) -> Result<(), LoxError> {
+        self.check_expr(expression)?;
+        Ok(())
+    }
+
+    fn visit_print_stmt(&mut self, expression: &Expr) -> Result<(), LoxError> {
+        self.check_expr(expression)?;
+        Ok(())
+    }
+
+    fn visit_var_stmt(
+        &mut self,
+        target: &BindingPattern,
+        annotation: &Option<TypeAnnotation>,
+        initializer: &Option<Expr>,
+    ) -> Result<(), LoxError> {
+        // NOTE: `var x: Number;` is fine, it gets its value later.
+        if let Some(initializer) = initializer {
+            let value = self.check_expr(initializer)?;
+            let token = match target {
+                BindingPattern::Name(name) => name,
+                BindingPattern::List { bracket, .. } => bracket,
+                BindingPattern::Map { brace, .. } => brace,
+            };
+            self.check_annotation(token, annotation, value);
+        }
+
+        let declared = annotation.as_ref().map_or(Type::Any, |a| a.ty);
+        self.declare_target(target, declared);
+        Ok(())
+    }
+
+    fn visit_const_stmt(
+        &mut self,
+        name: &Token,
+        annotation: &Option<TypeAnnotation>,
+        initializer: &Expr,
+    ) -> Result<(), LoxError> {
+        let value = self.check_expr(initializer)?;
+        self.check_annotation(name, annotation, value);
+
+        // A constant never changes, so without an annotation its value tells us the type.
+        let declared = annotation.as_ref().map_or(value, |a| a.ty);
+        self.declare(name, declared, None);
+        Ok(())
+    }
+
+    fn visit_if_statement(
+        &mut self,
+        condition: &Expr,
+        then_branch: &Stmt,
+        else_branch: &Option<Stmt>,
+    ) -> Result<(), LoxError> {
+        self.check_expr(condition)?;
+        self.check_stmt(then_branch)?;
+        if let Some(else_branch) = else_branch {
+            self.check_stmt(else_branch)?;
+        }
+        Ok(())
+    }
+
+    fn visit_while_statement(
+        &mut self,
+        condition: &Expr,
+        body: &Box<Stmt>,
+        increment: &Option<Expr>,
+        _label: &Option<Token>,
+    ) -> Result<(), LoxError> {
+        self.check_expr(condition)?;
+        self.check_stmt(body)?;
+        if let Some(increment) = increment {
+            self.check_expr(increment)?;
+        }
+        Ok(())
+    }
+
+    fn visit_for_in_stmt(
+        &mut self,
+        variable: &Token,
+        iterable: &Expr,
+        body: &Stmt,
+        _label: &Option<Token>,
+    ) -> Result<(), LoxError> {
+        let iterable_type = self.check_expr(iterable)?;
+        self.check_container(
+            variable,
+            iterable_type,
+            &[Type::String, Type::List, Type::Map, Type::Function],
+            "Can only iterate over strings, lists, maps, ranges and functions",
+        );
+
+        self.scoped(|checker| {
+            checker.declare(variable, Type::Any, None);
+            checker.check_stmt(body)
+        })
+    }
+
+    fn visit_do_while_stmt(
+        &mut self,
+        body: &Stmt,
+        condition: &Expr,
+        _label: &Option<Token>,
+    ) -> Result<(), LoxError> {
+        self.check_stmt(body)?;
+        self.check_expr(condition)?;
+        Ok(())
+    }
+
+    fn visit_loop_stmt(&mut self, body: &Stmt, _label: &Option<Token>) -> Result<(), LoxError> {
+        self.check_stmt(body)
+    }
+
+    fn visit_match_stmt(
+        &mut self,
+        _keyword: &Token,
+        subject: &Expr,
+        arms: &Vec<MatchArm>,
+    ) -> Result<(), LoxError> {
+        self.check_expr(subject)?;
+        for arm in arms {
+            self.scoped(|checker| {
+                for pattern in &arm.patterns {
+                    if let Pattern::Binding(name) = pattern {
+                        checker.declare(name, Type::Any, None);
+                    }
+                }
+                if let Some(guard) = &arm.guard {
+                    checker.check_expr(guard)?;
+                }
+                checker.check_stmt(&arm.body)
+            })?;
+        }
+        Ok(())
+    }
+
+    fn visit_break_stmt(
+        &mut self,
+        _keyword: &Token,
+        _label: &Option<Token>,
+    ) -> Result<(), LoxError> {
+        Ok(())
+    }
+
+    fn visit_continue_stmt(
+        &mut self,
+        _keyword: &Token,
+        _label: &Option<Token>,
+    ) -> Result<(), LoxError> {
+        Ok(())
+    }
+
+    fn visit_function_stmt(
+        &mut self,
+        name: &Token,
+        paramaters: &Vec<Parameter>,
+        return_type: &Option<TypeAnnotation>,
+        body: &Vec<Stmt>,
+    ) -> Result<(), LoxError> {
+        let param_type = |param: &Parameter| match (&param.annotation, param.is_rest) {
+            (Some(annotation), _) => annotation.ty,
+            (None, true) => Type::List,
+            (None, false) => Type::Any,
+        };
+        let signature = Rc::new(Signature {
+            name: name.lexeme.clone(),
+            params: paramaters
+                .iter()
+                .filter(|param| !param.is_rest)
+                .map(|param| {
+                    let name = param.target.name().map(|name| name.lexeme.clone());
+                    (name, param_type(param))
+                })
+                .collect(),
+            returns: return_type.as_ref().map_or(Type::Any, |a| a.ty),
+        });
+        // NOTE: A function's name can be reassigned like any var, so it only keeps the Function type
+        // when something about it is annotated. The signature is there for checking calls either way.
+        let is_annotated =
+            return_type.is_some() || paramaters.iter().any(|param| param.annotation.is_some());
+        let ty = if is_annotated {
+            Type::Function
+        } else {
+            Type::Any
+        };
+        // Declared before the body gets checked so that recursive calls know about it.
+        self.declare(name, ty, Some(Rc::clone(&signature)));
+
+        self.functions
+            .push((name.lexeme.clone(), signature.returns));
+        let result = self.scoped(|checker| {
+            // Parameters and the body share the same environment, same as in the interpreter.
+            for param in paramaters {
+                if let Some(default) = &param.default {
+                    let value = checker.check_expr(default)?;
+                    if let BindingPattern::Name(param_name) = &param.target {
+                        checker.check_annotation(param_name, &param.annotation, value);
+                    }
+                }
+                checker.declare_target(&param.target, param_type(param));
+            }
+            body.iter()
+                .try_for_each(|statement| checker.check_stmt(statement))
+        });
+        self.functions.pop();
+        result
+    }
+
+    fn visit_return_stmt(&mut self, keyword: &Token, value: &Option<Expr>) -> Result<(), LoxError> {
+        let actual = match value {
+            Some(value) => self.check_expr(value)?,
+            None => Type::Nil,
+        };
+
+        if let Some((function, expected)) = self.functions.last().cloned() {
+            if !expected.accepts(actual) {
+                self.error(
+                    keyword,
+                    &format!(
+                        "'{}' should return {}, but returns {}.",
+                        function, expected, actual
+                    ),
+                );
+            }
+        }
+        Ok(())
+    }
+}
+
+#[test]
+fn test_annotations_are_checked() {
+    use crate::lox_interpreter::{parser::Parser, scanner::Scanner};
+
+    let check = |source: &str| {
+        let tokens = Scanner::new(source.into()).scan_tokens().unwrap();
+        let statements = Parser::new(tokens).parse().unwrap();
+        TypeChecker::new().check(&statements)
+    };
+
+    // Unannotated code is left alone.
+    assert!(check("var x = 1; x = \"one\"; fun f(a) { return a; } f(1) + 1;").is_ok());
+    assert!(check("fun f() { return 1; } f = 3; print f + 1;").is_ok());
+    assert!(check("var x: Number = 1 + 2 * 3; const s = \"a\" + \"b\";").is_ok());
+    assert!(
+        check("fun f(a: Number, b: String = \"b\"): Number { return a; } f(1, b: \"c\");").is_ok()
+    );
+
+    assert!(check("var x: Number = \"one\";").is_err());
+    assert!(check("var x: Number; x = true;").is_err());
+    assert!(check("fun f(a: Number) {} f(\"a\");").is_err());
+    assert!(check("fun f(a: Number) {} f(a: nil);").is_err());
+    assert!(check("fun f(): String { return 1; }").is_err());
+    assert!(check("const s = \"a\"; print -s;").is_err());
+    assert!(check("print \"a\" - 1;").is_err());
+}
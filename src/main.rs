@@ -1,7 +1,7 @@
 // TODO: RIGHT NOW I'M NOT LOOKING AT PERFORMANCE, BUT AT SOME PONITN I SHOULD. DON'T FORGET TO DO
 // THAT.
-use lox_interpreter::{error::LoxError, Lox};
-use std::env;
+use lox_interpreter::{error::LoxError, AstDump, Lox};
+use std::{env, time::Duration};
 
 pub mod lox_interpreter;
 
@@ -10,10 +10,43 @@ fn main() -> Result<(), LoxError> {
     let mut lox = Lox::new();
     // println!("{:#?}", args);
 
-    if args.len() > 2 {
-        return Err(LoxError::Error("Usage: jlox [script]".to_string()));
-    } else if args.len() == 2 {
-        let _ = lox.run_file(args[1].clone());
+    let usage = || {
+        LoxError::Error(
+            "Usage: jlox [--optimize] [--sandbox] [--seed n] [--timeout ms] [--dump-ast | --dump-tree | --dump-json] [script [args...]]"
+                .to_string(),
+        )
+    };
+    let mut script = None;
+    let mut args = args.into_iter().skip(1);
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--optimize" | "-O" => lox.set_optimize(true),
+            "--sandbox" => lox.set_sandboxed(true),
+            // NOTE: Parsed as an i64 so `--seed n` lines up with what `seed(n)` does in a script.
+            "--seed" => match args.next().and_then(|seed| seed.parse::<i64>().ok()) {
+                Some(seed) => lox.set_seed(Some(seed as u64)),
+                None => return Err(usage()),
+            },
+            "--timeout" => match args.next().and_then(|ms| ms.parse::<u64>().ok()) {
+                Some(ms) => lox.set_timeout(Some(Duration::from_millis(ms))),
+                None => return Err(usage()),
+            },
+            "--dump-ast" => lox.set_dump(Some(AstDump::Sexpr)),
+            "--dump-tree" => lox.set_dump(Some(AstDump::Tree)),
+            "--dump-json" => lox.set_dump(Some(AstDump::Json)),
+            // A lone `-` is the script, read from stdin.
+            flag if flag.starts_with('-') && flag != "-" => return Err(usage()),
+            // NOTE: Everything after the script belongs to the script, flags included.
+            _ => {
+                script = Some(arg);
+                lox.set_script_args(args.collect());
+                break;
+            }
+        }
+    }
+
+    if let Some(script) = script {
+        let _ = lox.run_file(script);
     } else {
         let _ = lox.run_prompt();
     }